@@ -1,19 +1,36 @@
 // 配置相关命令
 
-use crate::models::AppConfig;
-use crate::services::ConfigService;
+use crate::error::{AppError, ErrorResponse};
+use crate::models::{AppConfig, ProxyConfig};
+use crate::services::{ClipboardWatcher, ConfigService, DownloadManager};
+use crate::utils::http::build_client;
+use crate::utils::i18n::{set_language, tr, Text};
+use std::time::{Duration, Instant};
+use tauri::State;
+
+/// 代理测试地址（返回 204 空响应）
+const PROXY_TEST_URL: &str = "https://www.youtube.com/generate_204";
+
+/// 代理测试超时
+const PROXY_TEST_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// 获取配置目录路径
-fn get_config_dir() -> Result<std::path::PathBuf, String> {
+pub(crate) fn get_config_dir() -> Result<std::path::PathBuf, AppError> {
     dirs::config_dir()
         .map(|p| p.join("youtube-downloader-tool"))
-        .ok_or_else(|| "无法获取配置目录".to_string())
+        .ok_or_else(|| AppError::ConfigError(tr(Text::ConfigDirUnavailable, &[])))
+}
+
+/// 读取当前配置
+pub(crate) fn load_config() -> Result<AppConfig, AppError> {
+    let config_dir = get_config_dir()?;
+    ConfigService::new(config_dir)?.load()
 }
 
 /// 获取配置
 /// 需求：9.1, 9.3
 #[tauri::command]
-pub async fn get_config() -> Result<AppConfig, String> {
+pub async fn get_config() -> Result<AppConfig, ErrorResponse> {
     let config_dir = get_config_dir()?;
     let service = ConfigService::new(config_dir)?;
     service.load().map_err(|e| e.into())
@@ -22,8 +39,41 @@ pub async fn get_config() -> Result<AppConfig, String> {
 /// 保存配置
 /// 需求：9.1, 9.2, 9.5
 #[tauri::command]
-pub async fn save_config(config: AppConfig) -> Result<(), String> {
+pub async fn save_config(
+    config: AppConfig,
+    manager: State<'_, DownloadManager>,
+) -> Result<(), ErrorResponse> {
+    config.validate_rate_limits().map_err(AppError::ConfigError)?;
+
     let config_dir = get_config_dir()?;
     let service = ConfigService::new(config_dir)?;
-    service.save(&config).map_err(|e| e.into())
+    service.save(&config)?;
+    set_language(config.language);
+    ClipboardWatcher::set_enabled(config.clipboard_watch);
+
+    // 限速配置可能已变化，重新应用到正在下载的任务
+    manager.reapply_rate_limits(&config);
+    Ok(())
+}
+
+/// 测试代理连通性
+///
+/// 通过代理访问 YouTube，成功时返回耗时（毫秒）
+#[tauri::command]
+pub async fn test_proxy(proxy: ProxyConfig) -> Result<u64, ErrorResponse> {
+    let client = build_client(Some(&proxy.to_url()))?;
+
+    let started = Instant::now();
+    let response = client
+        .get(PROXY_TEST_URL)
+        .timeout(PROXY_TEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(AppError::NetworkError)?;
+
+    if let Err(e) = response.error_for_status() {
+        return Err(AppError::NetworkError(e).into());
+    }
+
+    Ok(started.elapsed().as_millis() as u64)
 }
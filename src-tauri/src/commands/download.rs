@@ -1,10 +1,17 @@
 // 下载相关命令
 
-use crate::error::AppError;
-use crate::models::{ProgressData, VideoInfo};
-use crate::services::YtdlpService;
+use super::config::load_config;
+use super::history::get_data_dir;
+use crate::error::{AppError, ErrorResponse};
+use crate::models::{is_valid_rate_limit, DownloadTask, DuplicateAction, TaskStatus, VideoInfo};
+use crate::services::{
+    build_report, DownloadManager, HistoryService, ImportReport, InfoCache, KnownUrls,
+    TaskService, YtdlpOptions, YtdlpService,
+};
+use crate::utils::i18n::{tr, Text};
+use crate::utils::url::normalize_video_url;
 use serde::Deserialize;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, State};
 
 /// 下载视频请求参数
 #[derive(Debug, Deserialize)]
@@ -14,13 +21,28 @@ pub struct DownloadVideoRequest {
     format_id: String,
     save_path: String,
     task_id: String,
+    /// 视频标题
+    #[serde(default)]
+    title: String,
+    /// 任务级限速（覆盖全局配置）
+    #[serde(default)]
+    rate_limit: Option<String>,
+    /// 计划开始时间戳（秒），为空或已过期时立即开始
+    #[serde(default)]
+    scheduled_at: Option<i64>,
+    /// 已下载过同一视频时的处理方式
+    #[serde(default)]
+    on_duplicate: DuplicateAction,
 }
 
 /// 获取视频信息
 /// 需求：1.1, 1.2, 1.3, 1.4
 #[tauri::command]
-pub async fn get_video_info(url: String) -> Result<VideoInfo, String> {
-    let service = YtdlpService::new()?;
+pub async fn get_video_info(url: String) -> Result<VideoInfo, ErrorResponse> {
+    let config = load_config()?;
+    let service = YtdlpService::new()?
+        .with_options(YtdlpOptions::from_config(&config))
+        .with_info_cache(InfoCache::new(get_data_dir()?)?);
     service.fetch_info(&url).await.map_err(|e| e.into())
 }
 
@@ -30,98 +52,148 @@ pub async fn get_video_info(url: String) -> Result<VideoInfo, String> {
 pub async fn download_video(
     request: DownloadVideoRequest,
     app_handle: AppHandle,
-) -> Result<String, String> {
-    eprintln!("[download_video] 收到下载请求:");
-    eprintln!("  - task_id: {}", request.task_id);
-    eprintln!("  - url: {}", request.url);
-    eprintln!("  - format_id: {}", request.format_id);
-    eprintln!("  - save_path: {}", request.save_path);
-    
-    let service = YtdlpService::new()?;
-
-    // 克隆数据用于异步任务
-    let task_id = request.task_id.clone();
-    let url = request.url.clone();
-    let format_id = request.format_id.clone();
-    let save_path = request.save_path.clone();
-    let app_handle_clone = app_handle.clone();
-    
-    // 在后台异步执行下载,不阻塞返回
-    tokio::spawn(async move {
-        eprintln!("[download_video] 开始执行下载...");
-        
-        // 创建进度回调函数
-        let progress_callback = {
-            let app_handle = app_handle_clone.clone();
-            move |progress: ProgressData| {
-                eprintln!("[download_video] 进度更新: {}%", progress.percent);
-                // 通过 Tauri 事件系统发送进度更新
-                let _ = app_handle.emit_all("download-progress", progress);
-            }
-        };
-
-        // 执行下载
-        let result = service
-            .download_video(
-                task_id.clone(),
-                url,
-                format_id,
-                save_path,
-                progress_callback,
-            )
-            .await;
-
-        match result {
-            Ok(file_path) => {
-                eprintln!("[download_video] 下载成功: {}", file_path);
-                
-                // 获取文件大小
-                let file_size = std::fs::metadata(&file_path)
-                    .map(|m| m.len())
-                    .unwrap_or(0);
-                
-                // 发送下载完成事件
-                let _ = app_handle_clone.emit_all(
-                    "download-complete",
-                    serde_json::json!({
-                        "taskId": task_id,
-                        "filePath": file_path,
-                        "fileSize": file_size,
-                    }),
-                );
-            }
-            Err(e) => {
-                eprintln!("[download_video] 下载失败: {:?}", e);
-                // 发送下载错误事件
-                let error_msg = e.user_message();
-                let _ = app_handle_clone.emit_all(
-                    "download-error",
-                    serde_json::json!({
-                        "taskId": task_id,
-                        "error": error_msg,
-                    }),
-                );
-            }
+    manager: State<'_, DownloadManager>,
+) -> Result<String, ErrorResponse> {
+    log::info!(
+        task_id = request.task_id.as_str();
+        "[download_video] 收到下载请求: url={}, format_id={}, save_path={}, rate_limit={:?}",
+        request.url,
+        request.format_id,
+        request.save_path,
+        request.rate_limit
+    );
+
+    if let Some(rate_limit) = &request.rate_limit {
+        if !is_valid_rate_limit(rate_limit) {
+            return Err(AppError::ConfigError(tr(Text::InvalidRateLimit, &[rate_limit])).into());
         }
-    });
+    }
+
+    let url = normalize_video_url(&request.url)?.url;
+    let now = chrono::Utc::now().timestamp();
+    let mut task = DownloadTask {
+        id: request.task_id.clone(),
+        url,
+        title: request.title,
+        format_id: request.format_id,
+        save_path: request.save_path,
+        status: TaskStatus::Pending,
+        created_at: now,
+        rate_limit: request.rate_limit,
+        scheduled_at: request.scheduled_at,
+        attempts: 0,
+        on_duplicate: request.on_duplicate,
+    };
+
+    // 未到计划时间的任务保存到任务列表，由调度器到期启动
+    if !task.is_due(now) {
+        log::info!(task_id = task.id.as_str(); "[download_video] 任务已加入计划: {:?}", task.scheduled_at);
+        TaskService::new(get_data_dir()?)?.add(task)?;
+        return Ok(request.task_id);
+    }
+
+    let config = load_config()?;
+    let data_dir = get_data_dir()?;
+    let service = YtdlpService::new()?.with_info_cache(InfoCache::new(&data_dir)?);
+    task.status = TaskStatus::Downloading;
+
+    // 在后台异步执行下载,不阻塞返回
+    manager.start(
+        app_handle,
+        service,
+        YtdlpOptions::from_config(&config),
+        task,
+        HistoryService::new(&data_dir)?,
+        &config,
+    )?;
 
     // 立即返回任务 ID,不等待下载完成
-    eprintln!("[download_video] 下载任务已启动,返回 task_id");
+    log::debug!(task_id = request.task_id.as_str(); "[download_video] 下载任务已启动");
     Ok(request.task_id)
 }
 
 /// 取消下载
 /// 需求：3.5
+///
+/// 尚未开始的计划任务直接从任务列表中移除
 #[tauri::command]
-pub async fn cancel_download(task_id: String) -> Result<(), String> {
-    let service = YtdlpService::new()?;
-    service.cancel_download(&task_id).await.map_err(|e| e.into())
+pub async fn cancel_download(
+    task_id: String,
+    manager: State<'_, DownloadManager>,
+) -> Result<(), ErrorResponse> {
+    match manager.cancel(&task_id) {
+        Err(AppError::TaskNotFound(_)) => {
+            TaskService::new(get_data_dir()?)?.remove(&task_id)?;
+            Ok(())
+        }
+        result => result.map_err(|e| e.into()),
+    }
+}
+
+/// 获取计划任务列表
+#[tauri::command]
+pub async fn get_scheduled_tasks() -> Result<Vec<DownloadTask>, ErrorResponse> {
+    let service = TaskService::new(get_data_dir()?)?;
+    service.load().map_err(|e| e.into())
+}
+
+/// 调整正在下载的任务的限速
+///
+/// `rate_limit` 为空时恢复使用全局配置，任务会以 `--continue` 重启续传
+#[tauri::command]
+pub async fn set_rate_limit(
+    task_id: String,
+    rate_limit: Option<String>,
+    manager: State<'_, DownloadManager>,
+) -> Result<(), ErrorResponse> {
+    let rate_limit = rate_limit.filter(|value| !value.trim().is_empty());
+    if let Some(value) = &rate_limit {
+        if !is_valid_rate_limit(value) {
+            return Err(AppError::ConfigError(tr(Text::InvalidRateLimit, &[value])).into());
+        }
+    }
+
+    let config = load_config()?;
+    manager
+        .set_task_rate_limit(&task_id, rate_limit, &config)
+        .map_err(|e| e.into())
+}
+
+/// 批量导入链接
+///
+/// 从文本或 .txt/.csv 文件（`file_path`）中提取链接，与下载历史、正在下载和计划中的任务去重，
+/// 返回逐行的校验结果
+#[tauri::command]
+pub async fn import_urls(
+    text: Option<String>,
+    file_path: Option<String>,
+    manager: State<'_, DownloadManager>,
+) -> Result<ImportReport, ErrorResponse> {
+    let text = match file_path {
+        Some(path) => String::from_utf8_lossy(&std::fs::read(path).map_err(AppError::from)?)
+            .into_owned(),
+        None => text.unwrap_or_default(),
+    };
+
+    let data_dir = get_data_dir()?;
+    let history = HistoryService::new(&data_dir)?.urls()?;
+    let scheduled = TaskService::new(&data_dir)?.load()?;
+    let running = manager.running_urls();
+
+    let known = KnownUrls::new(
+        history.iter().map(String::as_str),
+        scheduled
+            .iter()
+            .map(|task| task.url.as_str())
+            .chain(running.iter().map(String::as_str)),
+    );
+    Ok(build_report(&text, &known))
 }
 
 /// 获取 yt-dlp 版本
 /// 需求：6.1
 #[tauri::command]
-pub async fn get_ytdlp_version() -> Result<String, String> {
+pub async fn get_ytdlp_version() -> Result<String, ErrorResponse> {
     use std::process::Command;
 
     let ytdlp_path = if cfg!(target_os = "windows") {
@@ -136,8 +208,8 @@ pub async fn get_ytdlp_version() -> Result<String, String> {
             .output()
     })
     .await
-    .map_err(|e| AppError::YtdlpError(format!("任务执行失败: {}", e)))?
-    .map_err(|e| AppError::YtdlpError(format!("获取版本失败: {}", e)))?;
+    .map_err(|e| AppError::YtdlpError(tr(Text::ProcessFailed, &[&e])))?
+    .map_err(|e| AppError::YtdlpError(tr(Text::ProcessFailed, &[&e])))?;
 
     if output.status.success() {
         let version = String::from_utf8_lossy(&output.stdout)
@@ -1,11 +1,13 @@
+use crate::error::ErrorResponse;
+use crate::utils::i18n::{tr, Text};
 use crate::utils::fs::{normalize_path, open_path, verify_path_permission};
 use tauri::api::dialog::blocking::FileDialogBuilder;
 
 /// 选择文件夹
 #[tauri::command]
-pub async fn select_folder() -> Result<Option<String>, String> {
+pub async fn select_folder() -> Result<Option<String>, ErrorResponse> {
     let result = FileDialogBuilder::new()
-        .set_title("选择保存文件夹")
+        .set_title(&tr(Text::SelectFolderTitle, &[]))
         .pick_folder();
     
     Ok(result.map(|path| path.to_string_lossy().to_string()))
@@ -13,19 +15,19 @@ pub async fn select_folder() -> Result<Option<String>, String> {
 
 /// 验证路径权限
 #[tauri::command]
-pub async fn verify_path(path: String) -> Result<bool, String> {
+pub async fn verify_path(path: String) -> Result<bool, ErrorResponse> {
     verify_path_permission(&path).map_err(|e| e.into())
 }
 
 /// 打开文件或文件夹
 #[tauri::command]
-pub async fn open_file(path: String) -> Result<(), String> {
+pub async fn open_file(path: String) -> Result<(), ErrorResponse> {
     open_path(&path).map_err(|e| e.into())
 }
 
 /// 规范化路径
 #[tauri::command]
-pub async fn normalize_file_path(path: String) -> Result<String, String> {
+pub async fn normalize_file_path(path: String) -> Result<String, ErrorResponse> {
     normalize_path(&path)
         .map(|p| p.to_string_lossy().to_string())
         .map_err(|e| e.into())
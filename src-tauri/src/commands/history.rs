@@ -1,38 +1,234 @@
 // 历史记录相关命令
 
-use crate::models::HistoryItem;
-use crate::services::HistoryService;
+use crate::error::{AppError, ErrorResponse};
+use crate::models::{
+    DeleteResult, DuplicateCheck, HistoryFormat, HistoryImportResult, HistoryItem, HistoryPage,
+    HistoryQuery, MergeStrategy, RelinkReport,
+};
+use crate::services::{
+    export_archive, history_from_archive, history_to_csv, history_to_html, history_to_json,
+    parse_archive, parse_history_csv, parse_history_json, HistoryService,
+};
+use crate::utils::i18n::{tr, Text};
+use std::fs;
+use std::path::Path;
+use tauri::api::dialog::blocking::FileDialogBuilder;
 
 /// 获取数据目录路径
-fn get_data_dir() -> Result<std::path::PathBuf, String> {
+pub(crate) fn get_data_dir() -> Result<std::path::PathBuf, AppError> {
     dirs::data_dir()
         .map(|p| p.join("youtube-downloader-tool"))
-        .ok_or_else(|| "无法获取数据目录".to_string())
+        .ok_or_else(|| AppError::ConfigError(tr(Text::DataDirUnavailable, &[])))
 }
 
 /// 获取历史记录
 /// 需求：5.2
+///
+/// 文件存在性为上次检查的结果，不逐个检查文件；界面显示使用 `query_history` 分页读取，
+/// 只检查返回的记录
 #[tauri::command]
-pub async fn get_history() -> Result<Vec<HistoryItem>, String> {
+pub async fn get_history() -> Result<Vec<HistoryItem>, ErrorResponse> {
     let data_dir = get_data_dir()?;
     let service = HistoryService::new(data_dir)?;
-    service.load().map_err(|e| e.into())
+    service.list().map_err(|e| e.into())
+}
+
+/// 按条件查询历史记录（搜索、筛选、排序和分页）
+///
+/// 历史记录损坏并恢复后，第一次查询会附带恢复结果
+#[tauri::command]
+pub async fn query_history(query: HistoryQuery) -> Result<HistoryPage, ErrorResponse> {
+    let data_dir = get_data_dir()?;
+    let service = HistoryService::new(data_dir)?;
+    let mut page = service.query(&query)?;
+    page.recoveries = service.take_recoveries()?;
+    Ok(page)
 }
 
 /// 清空历史记录
 /// 需求：5.4
 #[tauri::command]
-pub async fn clear_history() -> Result<(), String> {
+pub async fn clear_history() -> Result<(), ErrorResponse> {
     let data_dir = get_data_dir()?;
     let service = HistoryService::new(data_dir)?;
     service.clear().map_err(|e| e.into())
 }
 
-/// 添加历史记录
-/// 需求：5.1
+/// 删除选中的历史记录
+///
+/// `delete_files` 为真时同时删除媒体文件及其缩略图、字幕等附属文件，逐条返回结果
+#[tauri::command]
+pub async fn delete_history_items(
+    ids: Vec<String>,
+    delete_files: bool,
+) -> Result<Vec<DeleteResult>, ErrorResponse> {
+    let data_dir = get_data_dir()?;
+    let service = HistoryService::new(data_dir)?;
+    let results = service.delete(&ids, delete_files)?;
+    log::info!(
+        "[delete_history_items] 删除 {}/{} 条记录",
+        results.iter().filter(|result| result.deleted).count(),
+        ids.len()
+    );
+    Ok(results)
+}
+
+/// 清理历史记录
+///
+/// 删除文件已不存在的记录（`missing_files`）和/或早于 `older_than_days` 天的记录，返回删除的条数
+#[tauri::command]
+pub async fn prune_history(
+    missing_files: bool,
+    older_than_days: Option<u32>,
+) -> Result<usize, ErrorResponse> {
+    let data_dir = get_data_dir()?;
+    let service = HistoryService::new(data_dir)?;
+    let before = older_than_days
+        .map(|days| chrono::Utc::now().timestamp_millis() - i64::from(days) * 86_400_000);
+    let count = service.prune(missing_files, before)?;
+    log::info!("[prune_history] 清理 {} 条记录", count);
+    Ok(count)
+}
+
+/// 重新检查所有历史记录的文件是否存在
+///
+/// 返回检查结果发生变化的记录数
+#[tauri::command]
+pub async fn refresh_history_files() -> Result<usize, ErrorResponse> {
+    let data_dir = get_data_dir()?;
+    let service = HistoryService::new(data_dir)?;
+    let count = service.refresh_file_exists()?;
+    log::info!("[refresh_history_files] {} 条记录的文件状态已更新", count);
+    Ok(count)
+}
+
+/// 下载文件夹移动后更新历史记录中的文件路径
+///
+/// 把 `old_root` 下的路径改到 `new_root` 下，返回修改的记录数
+#[tauri::command]
+pub async fn relocate_history(old_root: String, new_root: String) -> Result<usize, ErrorResponse> {
+    let data_dir = get_data_dir()?;
+    let service = HistoryService::new(data_dir)?;
+    let count = service.relocate(Path::new(old_root.trim()), Path::new(new_root.trim()))?;
+    log::info!("[relocate_history] {} -> {}: {} 条记录", old_root, new_root, count);
+    Ok(count)
+}
+
+/// 在选择的目录下按文件名和大小查找文件已不存在的记录，重新关联文件路径
 #[tauri::command]
-pub async fn add_history(item: HistoryItem) -> Result<(), String> {
+pub async fn relink_history(dir: String) -> Result<RelinkReport, ErrorResponse> {
     let data_dir = get_data_dir()?;
     let service = HistoryService::new(data_dir)?;
-    service.save(item).map_err(|e| e.into())
+    let report = service.relink(Path::new(&dir))?;
+    log::info!(
+        "[relink_history] 在 {} 下关联 {} 条记录，{} 条仍未找到",
+        dir,
+        report.relinked,
+        report.missing
+    );
+    Ok(report)
+}
+
+/// 导出历史记录到 `path`
+///
+/// 可用 `query` 只导出符合条件的记录（如某个月的下载），返回导出的条数
+#[tauri::command]
+pub async fn export_history(
+    format: HistoryFormat,
+    path: String,
+    query: Option<HistoryQuery>,
+) -> Result<usize, ErrorResponse> {
+    let service = HistoryService::new(get_data_dir()?)?;
+    let items = service.query_all(&query.unwrap_or_default())?;
+    let content = match format {
+        HistoryFormat::Json => history_to_json(&items)?,
+        HistoryFormat::Csv => history_to_csv(&items)?,
+        HistoryFormat::Html => history_to_html(&items),
+    };
+    fs::write(&path, content).map_err(AppError::from)?;
+    log::info!("[export_history] 导出 {} 条记录到 {} ({:?})", items.len(), path, format);
+    Ok(items.len())
+}
+
+/// 从导出的 JSON 或 CSV 文件导入历史记录
+///
+/// 按文件内容识别格式，已有记录按 `merge_strategy` 处理
+#[tauri::command]
+pub async fn import_history(
+    path: String,
+    merge_strategy: MergeStrategy,
+) -> Result<HistoryImportResult, ErrorResponse> {
+    let bytes = fs::read(&path).map_err(AppError::from)?;
+    let text = String::from_utf8_lossy(&bytes);
+    let items = if text.trim_start_matches('\u{feff}').trim_start().starts_with(['{', '[']) {
+        parse_history_json(&text)?
+    } else {
+        parse_history_csv(&text)?
+    };
+
+    let service = HistoryService::new(get_data_dir()?)?;
+    let result = service.import(items, merge_strategy)?;
+    log::info!("[import_history] 从 {} 导入历史记录 ({:?})", path, merge_strategy);
+    Ok(result)
+}
+
+/// 检查是否已下载过同一视频的同一格式
+///
+/// 返回最近一次下载的记录和文件是否仍然存在，由前端选择跳过、重新下载或保留两者
+#[tauri::command]
+pub async fn check_duplicate(url: String, format_id: String) -> Result<DuplicateCheck, ErrorResponse> {
+    let data_dir = get_data_dir()?;
+    let service = HistoryService::new(data_dir)?;
+    let item = service.find_duplicate(&url, &format_id)?;
+    Ok(DuplicateCheck {
+        file_exists: item.as_ref().is_some_and(|item| item.file_exists),
+        item,
+    })
+}
+
+/// 导入 yt-dlp 下载存档文件
+///
+/// 把存档中尚未记录的视频加入历史记录，返回新增的条数；取消选择时返回 `None`
+#[tauri::command]
+pub async fn import_download_archive() -> Result<Option<usize>, ErrorResponse> {
+    let Some(path) = FileDialogBuilder::new()
+        .set_title(&tr(Text::ImportArchiveTitle, &[]))
+        .add_filter("Archive", &["txt"])
+        .pick_file()
+    else {
+        return Ok(None);
+    };
+
+    let bytes = fs::read(&path).map_err(AppError::from)?;
+    let entries = parse_archive(&String::from_utf8_lossy(&bytes));
+
+    let service = HistoryService::new(get_data_dir()?)?;
+    let imported = history_from_archive(
+        &entries,
+        &service.list()?,
+        chrono::Utc::now().timestamp_millis(),
+    );
+    let count = imported.len();
+    service.append(imported)?;
+    log::info!("[import_download_archive] 从 {} 导入 {} 条记录", path.display(), count);
+    Ok(Some(count))
+}
+
+/// 把历史记录导出为 yt-dlp 下载存档文件
+///
+/// 返回导出的文件路径，取消时返回 `None`
+#[tauri::command]
+pub async fn export_download_archive() -> Result<Option<String>, ErrorResponse> {
+    let Some(dest) = FileDialogBuilder::new()
+        .set_title(&tr(Text::ExportArchiveTitle, &[]))
+        .set_file_name("archive.txt")
+        .add_filter("Archive", &["txt"])
+        .save_file()
+    else {
+        return Ok(None);
+    };
+
+    let service = HistoryService::new(get_data_dir()?)?;
+    fs::write(&dest, export_archive(&service.list()?)).map_err(AppError::from)?;
+    Ok(Some(dest.to_string_lossy().to_string()))
 }
@@ -0,0 +1,64 @@
+// 日志相关命令
+
+use super::history::get_data_dir;
+use crate::error::{AppError, ErrorResponse};
+use crate::services::{DownloadManager, TaskLog};
+use crate::utils::i18n::{tr, Text};
+use crate::utils::logger;
+use std::path::PathBuf;
+use tauri::api::dialog::blocking::FileDialogBuilder;
+use tauri::State;
+
+/// 默认返回的日志行数
+const DEFAULT_LOG_LINES: usize = 500;
+
+/// 获取日志目录路径
+pub(crate) fn get_log_dir() -> Result<PathBuf, AppError> {
+    Ok(get_data_dir()?.join("logs"))
+}
+
+/// 获取最近的日志
+///
+/// 指定 `task_id` 时只返回该任务的日志
+#[tauri::command]
+pub async fn get_logs(
+    task_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<String>, ErrorResponse> {
+    let log_dir = get_log_dir()?;
+    logger::read_recent(&log_dir, task_id.as_deref(), limit.unwrap_or(DEFAULT_LOG_LINES))
+        .map_err(|e| e.into())
+}
+
+/// 导出日志文件
+///
+/// 用户选择保存位置后返回导出的文件路径，取消时返回 `None`
+#[tauri::command]
+pub async fn export_logs() -> Result<Option<String>, ErrorResponse> {
+    let file_name = format!(
+        "youtube-downloader-{}.log",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+    let Some(dest) = FileDialogBuilder::new()
+        .set_title(&tr(Text::ExportLogsTitle, &[]))
+        .set_file_name(&file_name)
+        .add_filter("Log", &["log", "txt"])
+        .save_file()
+    else {
+        return Ok(None);
+    };
+
+    logger::export(&get_log_dir()?, &dest)?;
+    Ok(Some(dest.to_string_lossy().to_string()))
+}
+
+/// 获取任务的 yt-dlp 输出记录（包括执行的命令行）
+#[tauri::command]
+pub async fn get_task_log(
+    task_id: String,
+    manager: State<'_, DownloadManager>,
+) -> Result<TaskLog, ErrorResponse> {
+    manager
+        .task_log(&task_id)
+        .ok_or_else(|| AppError::TaskNotFound(task_id).into())
+}
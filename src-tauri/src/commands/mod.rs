@@ -4,11 +4,20 @@ pub mod config;
 pub mod download;
 pub mod fs;
 pub mod history;
+pub mod logs;
 pub mod update;
 
 // 导出所有命令
-pub use config::{get_config, save_config};
-pub use download::{cancel_download, download_video, get_video_info, get_ytdlp_version};
+pub use config::{get_config, save_config, test_proxy};
+pub use download::{
+    cancel_download, download_video, get_scheduled_tasks, get_video_info, get_ytdlp_version,
+    import_urls, set_rate_limit,
+};
 pub use fs::{normalize_file_path, open_file, select_folder, verify_path};
-pub use history::{add_history, clear_history, get_history};
+pub use history::{
+    check_duplicate, clear_history, delete_history_items, export_download_archive,
+    export_history, get_history, import_download_archive, import_history, prune_history,
+    query_history, refresh_history_files, relink_history, relocate_history,
+};
+pub use logs::{export_logs, get_logs, get_task_log};
 pub use update::{check_ytdlp_update, update_ytdlp};
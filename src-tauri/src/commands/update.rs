@@ -1,5 +1,7 @@
 // 更新相关命令
 
+use super::config::load_config;
+use crate::error::{AppError, ErrorResponse};
 use crate::models::UpdateInfo;
 use crate::services::UpdateService;
 use std::path::PathBuf;
@@ -14,21 +16,27 @@ fn get_ytdlp_path() -> PathBuf {
     }
 }
 
+/// 根据配置创建更新服务
+fn create_update_service() -> Result<UpdateService, AppError> {
+    let config = load_config()?;
+    Ok(UpdateService::new(get_ytdlp_path())
+        .with_proxy(config.proxy_url())
+        .with_mirrors(config.update_mirrors))
+}
+
 /// 检查 yt-dlp 更新
 /// 需求：6.1, 6.2, 6.3, 6.4
 #[tauri::command]
-pub async fn check_ytdlp_update() -> Result<UpdateInfo, String> {
-    let ytdlp_path = get_ytdlp_path();
-    let service = UpdateService::new(ytdlp_path);
+pub async fn check_ytdlp_update() -> Result<UpdateInfo, ErrorResponse> {
+    let service = create_update_service()?;
     service.check_update().await.map_err(|e| e.into())
 }
 
 /// 更新 yt-dlp
 /// 需求：6.5, 6.6, 6.7
 #[tauri::command]
-pub async fn update_ytdlp(app_handle: AppHandle) -> Result<(), String> {
-    let ytdlp_path = get_ytdlp_path();
-    let service = UpdateService::new(ytdlp_path);
+pub async fn update_ytdlp(app_handle: AppHandle) -> Result<(), ErrorResponse> {
+    let service = create_update_service()?;
 
     // 首先检查更新以获取下载 URL
     let update_info = service.check_update().await?;
@@ -1,3 +1,5 @@
+use crate::models::Language;
+use crate::utils::i18n::{current_language, render, Text};
 use serde::{Deserialize, Serialize};
 
 /// 应用程序错误类型
@@ -38,51 +40,134 @@ pub enum AppError {
 
     #[error("JSON 序列化/反序列化错误: {0}")]
     JsonError(#[from] serde_json::Error),
+
+    #[error("服务器拒绝访问 (HTTP 403)")]
+    HttpForbidden,
+
+    #[error("请求过于频繁 (HTTP 429)")]
+    RateLimited,
+
+    #[error("网络连接中断: {0}")]
+    NetworkInterrupted(String),
+
+    #[error("视频在当前地区不可用")]
+    GeoBlocked,
+
+    #[error("视频不存在或已被删除: {0}")]
+    VideoUnavailable(String),
+
+    #[error("私享视频")]
+    PrivateVideo,
+
+    #[error("需要登录")]
+    SignInRequired,
+
+    #[error("存储设备不可用: {0}")]
+    DriveUnavailable(String),
+
+    #[error("不支持播放列表或频道链接: {0}")]
+    PlaylistNotSupported(String),
+
+    #[error("数据库错误: {0}")]
+    DatabaseError(#[from] rusqlite::Error),
 }
 
 impl AppError {
-    /// 转换为用户友好的错误消息
+    /// 转换为用户友好的错误消息（使用当前界面语言）
     pub fn user_message(&self) -> String {
+        self.localized_message(current_language())
+    }
+
+    /// 转换为指定语言的用户友好错误消息
+    pub fn localized_message(&self, language: Language) -> String {
+        let (text, args): (Text, &[&dyn std::fmt::Display]) = match self {
+            AppError::YtdlpError(msg) => (Text::YtdlpFailed, &[msg]),
+            AppError::ParseError(_) => (Text::ParseFailed, &[]),
+            AppError::FileSystemError(e) => (Text::FileSystemFailed, &[e]),
+            AppError::NetworkError(_) => (Text::NetworkFailed, &[]),
+            AppError::ConfigError(msg) => (Text::ConfigInvalid, &[msg]),
+            AppError::InvalidUrl(_) => (Text::InvalidUrl, &[]),
+            AppError::UnsupportedSite(_) => (Text::UnsupportedSite, &[]),
+            AppError::TaskNotFound(_) => (Text::TaskNotFound, &[]),
+            AppError::PermissionDenied(_) => (Text::PermissionDenied, &[]),
+            AppError::InsufficientSpace => (Text::InsufficientSpace, &[]),
+            AppError::YtdlpNotFound => (Text::YtdlpNotFound, &[]),
+            AppError::JsonError(e) => (Text::DataFormatInvalid, &[e]),
+            AppError::HttpForbidden => (Text::HttpForbidden, &[]),
+            AppError::RateLimited => (Text::RateLimited, &[]),
+            AppError::NetworkInterrupted(_) => (Text::NetworkInterrupted, &[]),
+            AppError::GeoBlocked => (Text::GeoBlocked, &[]),
+            AppError::VideoUnavailable(_) => (Text::VideoUnavailable, &[]),
+            AppError::PrivateVideo => (Text::PrivateVideo, &[]),
+            AppError::SignInRequired => (Text::SignInRequired, &[]),
+            AppError::DriveUnavailable(drive) => (Text::DriveUnavailable, &[drive]),
+            AppError::PlaylistNotSupported(_) => (Text::PlaylistNotSupported, &[]),
+            AppError::DatabaseError(e) => (Text::DatabaseFailed, &[e]),
+        };
+        render(text, language, args)
+    }
+
+    /// 稳定的错误代码，与变体同名
+    pub fn code(&self) -> &'static str {
         match self {
-            AppError::YtdlpError(msg) => format!("下载工具执行失败：{}", msg),
-            AppError::ParseError(_) => "视频信息解析失败，请检查链接是否正确".to_string(),
-            AppError::FileSystemError(e) => format!("文件操作失败：{}", e),
-            AppError::NetworkError(_) => "网络连接失败，请检查网络设置".to_string(),
-            AppError::ConfigError(msg) => format!("配置错误：{}", msg),
-            AppError::InvalidUrl(_) => "无效的视频链接，请输入正确的 URL".to_string(),
-            AppError::UnsupportedSite(_) => {
-                "该网站暂不支持，yt-dlp 支持 1000+ 网站，请尝试其他链接".to_string()
-            }
-            AppError::TaskNotFound(_) => "下载任务不存在".to_string(),
-            AppError::PermissionDenied(_) => "没有写入权限，请选择其他保存位置".to_string(),
-            AppError::InsufficientSpace => "磁盘空间不足，请清理磁盘后重试".to_string(),
-            AppError::YtdlpNotFound => "yt-dlp 未找到，请尝试更新或重新安装".to_string(),
-            AppError::JsonError(e) => format!("数据格式错误：{}", e),
+            AppError::YtdlpError(_) => "YtdlpError",
+            AppError::ParseError(_) => "ParseError",
+            AppError::FileSystemError(_) => "FileSystemError",
+            AppError::NetworkError(_) => "NetworkError",
+            AppError::ConfigError(_) => "ConfigError",
+            AppError::InvalidUrl(_) => "InvalidUrl",
+            AppError::UnsupportedSite(_) => "UnsupportedSite",
+            AppError::TaskNotFound(_) => "TaskNotFound",
+            AppError::PermissionDenied(_) => "PermissionDenied",
+            AppError::InsufficientSpace => "InsufficientSpace",
+            AppError::YtdlpNotFound => "YtdlpNotFound",
+            AppError::JsonError(_) => "JsonError",
+            AppError::HttpForbidden => "HttpForbidden",
+            AppError::RateLimited => "RateLimited",
+            AppError::NetworkInterrupted(_) => "NetworkInterrupted",
+            AppError::GeoBlocked => "GeoBlocked",
+            AppError::VideoUnavailable(_) => "VideoUnavailable",
+            AppError::PrivateVideo => "PrivateVideo",
+            AppError::SignInRequired => "SignInRequired",
+            AppError::DriveUnavailable(_) => "DriveUnavailable",
+            AppError::PlaylistNotSupported(_) => "PlaylistNotSupported",
+            AppError::DatabaseError(_) => "DatabaseError",
         }
     }
-}
 
-/// 转换为 Tauri 命令返回的 String 错误
-impl From<AppError> for String {
-    fn from(error: AppError) -> Self {
-        error.user_message()
+    /// 是否为临时性错误，可以自动重试
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            AppError::HttpForbidden
+                | AppError::RateLimited
+                | AppError::NetworkInterrupted(_)
+                | AppError::NetworkError(_)
+        )
     }
 }
 
-/// 错误响应结构（用于前端）
-#[allow(dead_code)]
+/// 错误响应结构（Tauri 命令返回给前端）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ErrorResponse {
+    /// 稳定的错误代码（与 `AppError` 变体同名），前端据此区分错误类型
     pub error_type: String,
+    /// 用户友好的错误消息
     pub message: String,
+    /// 技术细节（原始错误信息），用于日志和问题排查
+    pub details: String,
+    /// 是否可以重试
+    pub retryable: bool,
 }
 
 impl From<AppError> for ErrorResponse {
     fn from(error: AppError) -> Self {
         ErrorResponse {
-            error_type: format!("{:?}", error).split('(').next().unwrap_or("Unknown").to_string(),
+            error_type: error.code().to_string(),
             message: error.user_message(),
+            details: error.to_string(),
+            retryable: error.is_retryable(),
         }
     }
 }
@@ -113,9 +198,27 @@ mod tests {
                 std::io::ErrorKind::InvalidData,
                 "invalid json",
             ))),
+            AppError::HttpForbidden,
+            AppError::RateLimited,
+            AppError::NetworkInterrupted("Connection reset by peer".to_string()),
+            AppError::GeoBlocked,
+            AppError::VideoUnavailable("ERROR: HTTP Error 404: Not Found".to_string()),
+            AppError::PrivateVideo,
+            AppError::SignInRequired,
+            AppError::DriveUnavailable("E:\\".to_string()),
+            AppError::PlaylistNotSupported("https://www.youtube.com/@channel".to_string()),
+            AppError::DatabaseError(rusqlite::Error::InvalidQuery),
         ];
 
         for error in test_cases {
+            // 验证错误代码与变体名称一致
+            let variant = format!("{:?}", error);
+            assert_eq!(
+                variant.split('(').next().unwrap(),
+                error.code(),
+                "错误代码应与变体名称一致"
+            );
+
             // 验证 user_message() 返回非空字符串
             let user_msg = error.user_message();
             assert!(
@@ -152,6 +255,12 @@ mod tests {
         let json = serde_json::to_string(&response).expect("应该能够序列化为 JSON");
         assert!(json.contains("errorType"));
         assert!(json.contains("message"));
+        assert!(json.contains("\"details\":\"yt-dlp 执行失败: test\""));
+        assert!(json.contains("\"retryable\":false"));
+
+        let response: ErrorResponse = AppError::RateLimited.into();
+        assert_eq!(response.error_type, "RateLimited");
+        assert!(response.retryable);
     }
 
     #[test]
@@ -164,6 +273,8 @@ mod tests {
             (AppError::InvalidUrl("test".to_string()), "InvalidUrl"),
             (AppError::InsufficientSpace, "InsufficientSpace"),
             (AppError::YtdlpNotFound, "YtdlpNotFound"),
+            (AppError::HttpForbidden, "HttpForbidden"),
+            (AppError::NetworkInterrupted("test".to_string()), "NetworkInterrupted"),
         ];
 
         for (error, expected_type) in errors {
@@ -174,4 +285,31 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(AppError::HttpForbidden.is_retryable());
+        assert!(AppError::RateLimited.is_retryable());
+        assert!(AppError::NetworkInterrupted("timed out".to_string()).is_retryable());
+
+        assert!(!AppError::GeoBlocked.is_retryable());
+        assert!(!AppError::PrivateVideo.is_retryable());
+        assert!(!AppError::SignInRequired.is_retryable());
+        assert!(!AppError::InsufficientSpace.is_retryable());
+        assert!(!AppError::YtdlpError("unknown".to_string()).is_retryable());
+    }
+
+    #[test]
+    fn test_localized_message() {
+        let error = AppError::DriveUnavailable("E:\\".to_string());
+        assert_eq!(
+            error.localized_message(Language::ZhCn),
+            "保存位置所在的磁盘 E:\\ 未连接，请插入设备或选择其他保存位置"
+        );
+        assert!(error.localized_message(Language::EnUs).starts_with("The drive E:\\ "));
+        assert_eq!(
+            AppError::InsufficientSpace.localized_message(Language::EnUs),
+            "Not enough disk space. Please free up some space and try again"
+        );
+    }
 }
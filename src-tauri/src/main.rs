@@ -8,13 +8,49 @@ mod services;
 mod utils;
 
 use commands::{
-    add_history, cancel_download, check_ytdlp_update, clear_history, download_video, get_config,
-    get_history, get_video_info, get_ytdlp_version, normalize_file_path, open_file, save_config,
-    select_folder, update_ytdlp, verify_path,
+    cancel_download, check_duplicate, check_ytdlp_update, clear_history, delete_history_items,
+    download_video, export_download_archive, export_history, export_logs, get_config, get_history,
+    get_logs, get_scheduled_tasks, get_task_log, get_video_info, get_ytdlp_version,
+    import_download_archive, import_history, import_urls, normalize_file_path, open_file,
+    prune_history, query_history, refresh_history_files, relink_history, relocate_history,
+    save_config, select_folder, set_rate_limit, test_proxy, update_ytdlp, verify_path,
 };
+use services::{ClipboardWatcher, DownloadManager, HistoryService, Scheduler};
+use tauri::Manager;
 
 fn main() {
+    // 发布版本没有控制台窗口，日志写入数据目录下的文件
+    if let Err(e) = commands::logs::get_log_dir().and_then(|dir| utils::logger::init(&dir)) {
+        eprintln!("日志初始化失败: {}", e);
+    }
+
     tauri::Builder::default()
+        .manage(DownloadManager::default())
+        .setup(|app| {
+            // 按配置设置后端提示信息的语言和剪贴板监听
+            if let Ok(config) = commands::config::load_config() {
+                utils::i18n::set_language(config.language);
+                ClipboardWatcher::set_enabled(config.clipboard_watch);
+            }
+
+            // 初始化历史记录数据库（升级、恢复和快照），之后各命令创建服务时不再重复
+            let config_dir = commands::config::get_config_dir()?;
+            let data_dir = commands::history::get_data_dir()?;
+            if let Err(e) = HistoryService::init(&data_dir) {
+                log::error!("[history] 初始化历史记录失败: {}", e);
+            }
+
+            // 启动计划任务调度器
+            let manager = app.state::<DownloadManager>().inner().clone();
+            let scheduler =
+                Scheduler::new(app.handle(), manager, config_dir.clone(), data_dir.clone());
+            tauri::async_runtime::spawn(scheduler.run());
+
+            // 启动剪贴板监听（未开启时不读取剪贴板）
+            let watcher = ClipboardWatcher::new(app.handle(), config_dir, data_dir);
+            tauri::async_runtime::spawn(watcher.run());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // 文件系统命令
             select_folder,
@@ -25,14 +61,32 @@ fn main() {
             get_video_info,
             download_video,
             cancel_download,
+            set_rate_limit,
+            get_scheduled_tasks,
+            import_urls,
             get_ytdlp_version,
             // 配置命令
             get_config,
             save_config,
+            test_proxy,
             // 历史记录命令
             get_history,
+            query_history,
             clear_history,
-            add_history,
+            delete_history_items,
+            prune_history,
+            refresh_history_files,
+            relocate_history,
+            relink_history,
+            check_duplicate,
+            import_download_archive,
+            export_download_archive,
+            export_history,
+            import_history,
+            // 日志命令
+            get_logs,
+            export_logs,
+            get_task_log,
             // 更新命令
             check_ytdlp_update,
             update_ytdlp,
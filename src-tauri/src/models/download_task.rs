@@ -19,6 +19,41 @@ pub struct DownloadTask {
     pub status: TaskStatus,
     /// 创建时间戳
     pub created_at: i64,
+    /// 任务级限速（覆盖全局配置），为空时使用全局配置
+    #[serde(default)]
+    pub rate_limit: Option<String>,
+    /// 计划开始时间戳，为空时立即开始
+    #[serde(default)]
+    pub scheduled_at: Option<i64>,
+    /// 已执行的下载尝试次数（含自动重试）
+    #[serde(default)]
+    pub attempts: u32,
+    /// 已下载过同一视频时的处理方式
+    #[serde(default)]
+    pub on_duplicate: DuplicateAction,
+}
+
+impl DownloadTask {
+    /// 判断任务在给定时间是否已到计划开始时间
+    pub fn is_due(&self, now: i64) -> bool {
+        self.scheduled_at.is_none_or(|at| at <= now)
+    }
+}
+
+/// 重复下载的处理方式
+///
+/// 选择跳过时前端不发起下载；用户明确选择重新下载或保留两者时不使用下载存档，
+/// 否则 yt-dlp 会直接跳过存档中已有的视频
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DuplicateAction {
+    /// 没有重复（或未检查），正常下载
+    #[default]
+    New,
+    /// 重新下载，覆盖之前的文件
+    Redownload,
+    /// 保留之前的文件，新文件使用不同的文件名
+    KeepBoth,
 }
 
 /// 任务状态
@@ -36,6 +71,8 @@ pub enum TaskStatus {
     Failed,
     /// 已取消
     Cancelled,
+    /// 下载存档中已有该视频，已跳过
+    Skipped,
 }
 
 #[cfg(test)]
@@ -55,6 +92,10 @@ mod tests {
             save_path: "/downloads/video.mp4".to_string(),
             status: TaskStatus::Downloading,
             created_at: 1234567890,
+            rate_limit: None,
+            scheduled_at: None,
+            attempts: 0,
+            on_duplicate: DuplicateAction::default(),
         };
 
         let json = serde_json::to_string(&task).unwrap();
@@ -63,6 +104,30 @@ mod tests {
         assert!(json.contains("\"createdAt\":1234567890")); // 验证 camelCase
     }
 
+    #[test]
+    fn test_scheduled_task_is_due() {
+        let json = r#"{
+            "id": "task-1",
+            "url": "https://youtube.com/watch?v=test",
+            "title": "Test Video",
+            "formatId": "137",
+            "savePath": "/downloads/video.mp4",
+            "status": "pending",
+            "createdAt": 1234567890
+        }"#;
+
+        // 没有计划时间的旧数据立即到期
+        let mut task: DownloadTask = serde_json::from_str(json).unwrap();
+        assert_eq!(task.scheduled_at, None);
+        assert_eq!(task.attempts, 0);
+        assert_eq!(task.on_duplicate, DuplicateAction::New);
+        assert!(task.is_due(0));
+
+        task.scheduled_at = Some(1_700_000_000);
+        assert!(!task.is_due(1_699_999_999));
+        assert!(task.is_due(1_700_000_000));
+    }
+
     #[test]
     fn test_task_status_equality() {
         assert_eq!(TaskStatus::Completed, TaskStatus::Completed);
@@ -133,12 +198,24 @@ mod tests {
         let history_item = HistoryItem {
             id: task.id.clone(),
             title: task.title.clone(),
+            uploader: String::new(),
             url: task.url.clone(),
             resolution: "1080p".to_string(), // 简化处理
             file_path: task.save_path.clone(),
             file_size: file_content.len() as u64,
             downloaded_at: task.created_at,
             file_exists: true,
+            archive_id: None,
+            format_id: String::new(),
+            duration: 0,
+            thumbnail: String::new(),
+            extractor: String::new(),
+            video_id: String::new(),
+            vcodec: String::new(),
+            acodec: String::new(),
+            thumbnail_path: None,
+            subtitle_files: Vec::new(),
+            tags: Vec::new(),
         };
         history_store.add_item(history_item);
     }
@@ -172,6 +249,10 @@ mod tests {
                 save_path: format!("/downloads/{}.mp4", filename),
                 status: TaskStatus::Downloading,
                 created_at,
+                rate_limit: None,
+                scheduled_at: None,
+                attempts: 0,
+                on_duplicate: DuplicateAction::default(),
             };
 
             // 创建模拟的历史记录存储和文件系统
@@ -253,6 +334,10 @@ mod tests {
             save_path: "/downloads/incomplete.mp4".to_string(),
             status: TaskStatus::Downloading,
             created_at: 1234567890,
+            rate_limit: None,
+            scheduled_at: None,
+            attempts: 0,
+            on_duplicate: DuplicateAction::default(),
         };
 
         let history_store = MockHistoryStore::new();
@@ -272,6 +357,10 @@ mod tests {
             save_path: "/downloads/failed.mp4".to_string(),
             status: TaskStatus::Failed,
             created_at: 1234567890,
+            rate_limit: None,
+            scheduled_at: None,
+            attempts: 0,
+            on_duplicate: DuplicateAction::default(),
         };
 
         let history_store = MockHistoryStore::new();
@@ -291,6 +380,10 @@ mod tests {
             save_path: "/downloads/cancelled.mp4".to_string(),
             status: TaskStatus::Cancelled,
             created_at: 1234567890,
+            rate_limit: None,
+            scheduled_at: None,
+            attempts: 0,
+            on_duplicate: DuplicateAction::default(),
         };
 
         let history_store = MockHistoryStore::new();
@@ -8,6 +8,9 @@ pub struct HistoryItem {
     pub id: String,
     /// 视频标题
     pub title: String,
+    /// 上传者
+    #[serde(default)]
+    pub uploader: String,
     /// 视频 URL
     pub url: String,
     /// 下载的分辨率
@@ -20,6 +23,178 @@ pub struct HistoryItem {
     pub downloaded_at: i64,
     /// 文件是否存在
     pub file_exists: bool,
+    /// 下载存档标识（`<提取器> <视频 ID>`，与 yt-dlp 的 `--download-archive` 相同）
+    #[serde(default)]
+    pub archive_id: Option<String>,
+    /// 下载时选择的格式 ID（如 `137+140`，用于重复下载检查）
+    #[serde(default)]
+    pub format_id: String,
+    /// 时长（秒）
+    #[serde(default)]
+    pub duration: u32,
+    /// 缩略图 URL
+    #[serde(default)]
+    pub thumbnail: String,
+    /// 来源网站的提取器（如 `youtube`）
+    #[serde(default)]
+    pub extractor: String,
+    /// 视频 ID
+    #[serde(default)]
+    pub video_id: String,
+    /// 视频编码
+    #[serde(default)]
+    pub vcodec: String,
+    /// 音频编码
+    #[serde(default)]
+    pub acodec: String,
+    /// 本地缩略图文件
+    #[serde(default)]
+    pub thumbnail_path: Option<String>,
+    /// 字幕文件
+    #[serde(default)]
+    pub subtitle_files: Vec<String>,
+    /// 标签
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// 历史记录排序方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum HistorySort {
+    /// 下载时间从新到旧
+    #[default]
+    Newest,
+    /// 下载时间从旧到新
+    Oldest,
+    /// 文件从大到小
+    Largest,
+    /// 文件从小到大
+    Smallest,
+    /// 标题字母顺序
+    Title,
+}
+
+/// 历史记录查询条件
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryQuery {
+    /// 搜索关键词（匹配标题、上传者和链接）
+    pub search: Option<String>,
+    /// 下载时间下限（含）
+    pub from: Option<i64>,
+    /// 下载时间上限（含）
+    pub to: Option<i64>,
+    /// 分辨率
+    pub resolution: Option<String>,
+    /// 文件是否存在
+    pub file_exists: Option<bool>,
+    /// 文件大小下限（字节，含）
+    pub min_size: Option<u64>,
+    /// 文件大小上限（字节，含）
+    pub max_size: Option<u64>,
+    /// 排序方式
+    pub sort: HistorySort,
+    /// 上一页返回的游标，为空时从第一页开始
+    pub cursor: Option<String>,
+    /// 每页条数
+    pub limit: Option<usize>,
+}
+
+/// 历史记录查询结果（一页）
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPage {
+    pub items: Vec<HistoryItem>,
+    /// 下一页的游标，没有更多结果时为空
+    pub next_cursor: Option<String>,
+    /// 符合条件的总条数
+    pub total: usize,
+    /// 尚未告知用户的历史记录恢复结果，只在恢复后的第一次查询中返回
+    pub recoveries: Vec<HistoryRecovery>,
+}
+
+/// 历史记录损坏后的恢复结果
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryRecovery {
+    /// 损坏文件的备份路径
+    pub backup_path: String,
+    /// 恢复的记录数
+    pub recovered: usize,
+    /// 丢失的记录数（估计值），无法估计时为空
+    pub lost: Option<usize>,
+}
+
+/// 重复下载检查结果
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateCheck {
+    /// 最近一次下载同一视频、同一格式的历史记录
+    pub item: Option<HistoryItem>,
+    /// 之前下载的文件是否仍然存在
+    pub file_exists: bool,
+}
+
+/// 删除单条历史记录的结果
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteResult {
+    /// 记录 ID
+    pub id: String,
+    /// 记录是否已删除
+    pub deleted: bool,
+    /// 已删除的文件（媒体文件及其附属文件）
+    pub removed_files: Vec<String>,
+    /// 失败时的错误消息
+    pub error: Option<String>,
+}
+
+/// 历史记录导出格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum HistoryFormat {
+    /// 带版本号的 JSON，可完整导入
+    Json,
+    /// 表格（UTF-8 带 BOM，可直接用 Excel 打开），可导入
+    Csv,
+    /// 带缩略图的静态 HTML 报告，只用于查看
+    Html,
+}
+
+/// 导入历史记录时与已有记录的合并方式（按记录 ID 判断是否为同一条）
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MergeStrategy {
+    /// 保留已有记录，只添加新记录
+    #[default]
+    Skip,
+    /// 用导入的记录覆盖已有的同一条记录
+    Overwrite,
+    /// 清空现有历史记录后导入
+    Replace,
+}
+
+/// 导入历史记录的结果
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryImportResult {
+    /// 新增的记录数
+    pub added: usize,
+    /// 覆盖的记录数
+    pub updated: usize,
+    /// 因已存在而跳过的记录数
+    pub skipped: usize,
+}
+
+/// 重新关联移动过的文件的结果
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RelinkReport {
+    /// 找到新位置的记录数
+    pub relinked: usize,
+    /// 仍然找不到文件的记录数
+    pub missing: usize,
 }
 
 #[cfg(test)]
@@ -31,12 +206,24 @@ mod tests {
         let item = HistoryItem {
             id: "hist-123".to_string(),
             title: "Test Video".to_string(),
+            uploader: String::new(),
             url: "https://youtube.com/watch?v=test".to_string(),
             resolution: "1080p".to_string(),
             file_path: "/downloads/video.mp4".to_string(),
             file_size: 1024000,
             downloaded_at: 1234567890,
             file_exists: true,
+            archive_id: None,
+            format_id: String::new(),
+            duration: 0,
+            thumbnail: String::new(),
+            extractor: String::new(),
+            video_id: String::new(),
+            vcodec: String::new(),
+            acodec: String::new(),
+            thumbnail_path: None,
+            subtitle_files: Vec::new(),
+            tags: Vec::new(),
         };
 
         let json = serde_json::to_string(&item).unwrap();
@@ -8,9 +8,13 @@ mod history;
 mod update;
 
 pub use video_info::{VideoInfo, Format};
+pub use download_task::{DownloadTask, DuplicateAction, TaskStatus};
 pub use progress::ProgressData;
-pub use config::AppConfig;
+pub use config::{is_valid_rate_limit, AppConfig, Language, ProxyConfig, UpdateMirror};
 #[cfg(test)]
-pub use config::BrowserType;
-pub use history::HistoryItem;
+pub use config::{BrowserType, ProxyScheme};
+pub use history::{
+    DeleteResult, DuplicateCheck, HistoryFormat, HistoryImportResult, HistoryItem, HistoryPage,
+    HistoryQuery, HistoryRecovery, HistorySort, MergeStrategy, RelinkReport,
+};
 pub use update::UpdateInfo;
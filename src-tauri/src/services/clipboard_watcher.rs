@@ -0,0 +1,221 @@
+// 剪贴板监听
+// 开启后定时读取剪贴板，复制了新的视频链接时预先获取视频信息，
+// 通过 clipboard-url-detected 事件提示前端下载
+
+use crate::error::AppError;
+use crate::services::{ConfigService, HistoryService, InfoCache, YtdlpOptions, YtdlpService};
+use crate::utils::url::{normalize_video_url, NormalizedUrl, UrlKind};
+use reqwest::Url;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, ClipboardManager, Manager};
+
+/// 剪贴板检查间隔
+const CLIPBOARD_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 超过此长度的剪贴板内容不当作链接处理
+const MAX_CLIPBOARD_LEN: usize = 2048;
+
+/// 常见的 yt-dlp 支持的网站（YouTube 视频链接之外）
+const SUPPORTED_SITES: [&str; 15] = [
+    "bilibili.com",
+    "b23.tv",
+    "twitter.com",
+    "x.com",
+    "vimeo.com",
+    "tiktok.com",
+    "douyin.com",
+    "instagram.com",
+    "facebook.com",
+    "twitch.tv",
+    "dailymotion.com",
+    "soundcloud.com",
+    "nicovideo.jp",
+    "reddit.com",
+    "weibo.com",
+];
+
+/// 是否开启剪贴板监听（由配置控制）
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// 剪贴板监听器
+pub struct ClipboardWatcher {
+    app_handle: AppHandle,
+    config_dir: PathBuf,
+    data_dir: PathBuf,
+    /// 上一次读取的剪贴板内容
+    last_text: Option<String>,
+    /// 本次运行中已经提示过的链接
+    prompted: HashSet<String>,
+}
+
+impl ClipboardWatcher {
+    pub fn new(app_handle: AppHandle, config_dir: PathBuf, data_dir: PathBuf) -> Self {
+        Self {
+            app_handle,
+            config_dir,
+            data_dir,
+            last_text: None,
+            prompted: HashSet::new(),
+        }
+    }
+
+    /// 开启或关闭剪贴板监听
+    pub fn set_enabled(enabled: bool) {
+        ENABLED.store(enabled, Ordering::Relaxed);
+    }
+
+    /// 持续运行监听循环
+    ///
+    /// 关闭时不读取剪贴板；开启时剪贴板中已有的内容不提示
+    pub async fn run(mut self) {
+        let mut interval = tokio::time::interval(CLIPBOARD_POLL_INTERVAL);
+        let mut primed = false;
+
+        loop {
+            interval.tick().await;
+            if !ENABLED.load(Ordering::Relaxed) {
+                primed = false;
+                continue;
+            }
+
+            let text = match self.app_handle.clipboard_manager().read_text() {
+                Ok(text) => text,
+                Err(e) => {
+                    log::debug!("[clipboard] 读取剪贴板失败: {}", e);
+                    continue;
+                }
+            };
+            if !primed || text == self.last_text {
+                self.last_text = text;
+                primed = true;
+                continue;
+            }
+            self.last_text = text.clone();
+
+            if let Some(text) = text {
+                if let Err(e) = self.handle_text(&text).await {
+                    log::debug!("[clipboard] 无法获取剪贴板链接的视频信息: {:?}", e);
+                }
+            }
+        }
+    }
+
+    /// 处理新复制的文本，识别到新的视频链接时获取信息并通知前端
+    async fn handle_text(&mut self, text: &str) -> Result<(), AppError> {
+        let config = ConfigService::new(&self.config_dir)?.load()?;
+        let Some(normalized) = detect_video_url(text, &config.clipboard_ignore) else {
+            return Ok(());
+        };
+
+        // 同一链接只提示一次，已下载过的不再提示
+        if !self.prompted.insert(normalized.key()) {
+            return Ok(());
+        }
+        if HistoryService::new(&self.data_dir)?.contains_url(&normalized.url)? {
+            return Ok(());
+        }
+
+        log::info!("[clipboard] 检测到视频链接: {}", normalized.url);
+        let service = YtdlpService::new()?
+            .with_options(YtdlpOptions::from_config(&config))
+            .with_info_cache(InfoCache::new(&self.data_dir)?);
+        let info = service.fetch_info(&normalized.url).await?;
+
+        let _ = self.app_handle.emit_all(
+            "clipboard-url-detected",
+            serde_json::json!({
+                "url": normalized.url,
+                "info": info,
+            }),
+        );
+        Ok(())
+    }
+}
+
+/// 从剪贴板文本中识别可能受支持的视频链接
+///
+/// 只处理单个带协议的链接，忽略列表中的网站不处理
+fn detect_video_url(text: &str, ignore: &[String]) -> Option<NormalizedUrl> {
+    let text = text.trim();
+    if text.len() > MAX_CLIPBOARD_LEN || text.contains(char::is_whitespace) || !text.contains("://")
+    {
+        return None;
+    }
+
+    let normalized = normalize_video_url(text).ok()?;
+    let host = Url::parse(&normalized.url).ok()?.host_str()?.to_lowercase();
+    let supported = normalized.kind == UrlKind::Video
+        || SUPPORTED_SITES
+            .iter()
+            .any(|site| matches_domain(&host, site));
+    (supported && !is_ignored(&normalized.url, &host, ignore)).then_some(normalized)
+}
+
+/// 是否在忽略列表中（域名匹配包括子域名，以协议开头的项按前缀匹配）
+fn is_ignored(url: &str, host: &str, ignore: &[String]) -> bool {
+    ignore
+        .iter()
+        .map(|entry| entry.trim().to_lowercase())
+        .filter(|entry| !entry.is_empty())
+        .any(|entry| {
+            if entry.contains("://") {
+                url.to_lowercase().starts_with(&entry)
+            } else {
+                matches_domain(host, &entry)
+            }
+        })
+}
+
+/// 主机名是否为指定域名或其子域名
+fn matches_domain(host: &str, domain: &str) -> bool {
+    host == domain || host.ends_with(&format!(".{}", domain))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_video_url() {
+        let detected = detect_video_url(" https://youtu.be/dQw4w9WgXcQ?si=abc \n", &[]).unwrap();
+        assert_eq!(detected.url, "https://www.youtube.com/watch?v=dQw4w9WgXcQ");
+
+        let bilibili = detect_video_url("https://www.bilibili.com/video/BV1xx", &[]).unwrap();
+        assert_eq!(bilibili.kind, UrlKind::Unknown);
+
+        // 普通文本、多个词、未知网站、播放列表和单独的视频 ID 都不提示
+        for text in [
+            "hello world",
+            "看看这个 https://youtu.be/dQw4w9WgXcQ",
+            "https://example.com/article",
+            "https://www.youtube.com/playlist?list=PL123",
+            "dQw4w9WgXcQ",
+        ] {
+            assert!(detect_video_url(text, &[]).is_none(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_ignore_list() {
+        let ignore = vec![
+            "bilibili.com".to_string(),
+            " https://vimeo.com/channels/ ".to_string(),
+            String::new(),
+        ];
+
+        assert!(detect_video_url("https://m.bilibili.com/video/BV1xx", &ignore).is_none());
+        assert!(detect_video_url("https://vimeo.com/channels/staff/1", &ignore).is_none());
+        assert!(detect_video_url("https://vimeo.com/123456", &ignore).is_some());
+        assert!(detect_video_url("https://youtu.be/dQw4w9WgXcQ", &ignore).is_some());
+    }
+
+    #[test]
+    fn test_matches_domain() {
+        assert!(matches_domain("x.com", "x.com"));
+        assert!(matches_domain("mobile.x.com", "x.com"));
+        assert!(!matches_domain("box.com", "x.com"));
+    }
+}
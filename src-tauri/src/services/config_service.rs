@@ -1,5 +1,6 @@
 use crate::error::AppError;
 use crate::models::AppConfig;
+use crate::utils::i18n::{tr, Text};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -21,9 +22,7 @@ impl ConfigService {
         
         // 确保配置目录存在
         if !config_dir.exists() {
-            fs::create_dir_all(config_dir).map_err(|e| {
-                AppError::ConfigError(format!("无法创建配置目录: {}", e))
-            })?;
+            fs::create_dir_all(config_dir)?;
         }
         
         let config_path = config_dir.join("config.json");
@@ -53,14 +52,14 @@ impl ConfigService {
                     Ok(config) => Ok(config),
                     Err(e) => {
                         // 配置文件损坏，使用默认配置并恢复
-                        eprintln!("配置文件损坏: {}, 使用默认配置", e);
+                        log::warn!("配置文件损坏: {}, 使用默认配置", e);
                         self.recover_from_corruption()
                     }
                 }
             }
             Err(e) => {
                 // 读取失败，使用默认配置
-                eprintln!("无法读取配置文件: {}, 使用默认配置", e);
+                log::warn!("无法读取配置文件: {}, 使用默认配置", e);
                 self.recover_from_corruption()
             }
         }
@@ -82,14 +81,14 @@ impl ConfigService {
         
         // 写入临时文件
         fs::write(&temp_path, json).map_err(|e| {
-            AppError::ConfigError(format!("无法写入临时配置文件: {}", e))
+            AppError::ConfigError(tr(Text::ConfigSaveFailed, &[&e]))
         })?;
         
         // 原子重命名（在大多数文件系统上是原子操作）
         fs::rename(&temp_path, &self.config_path).map_err(|e| {
             // 如果重命名失败，清理临时文件
             let _ = fs::remove_file(&temp_path);
-            AppError::ConfigError(format!("无法保存配置文件: {}", e))
+            AppError::ConfigError(tr(Text::ConfigSaveFailed, &[&e]))
         })?;
         
         Ok(())
@@ -106,7 +105,7 @@ impl ConfigService {
         if self.config_path.exists() {
             let backup_path = self.config_path.with_extension("json.backup");
             if let Err(e) = fs::rename(&self.config_path, &backup_path) {
-                eprintln!("无法备份损坏的配置文件: {}", e);
+                log::warn!("无法备份损坏的配置文件: {}", e);
             }
         }
         
@@ -167,6 +166,15 @@ mod tests {
             auto_check_update: false,
             concurrent_downloads: 5,
             youtube_cookie_browser: crate::models::BrowserType::None,
+            update_mirrors: Vec::new(),
+            proxy: None,
+            rate_limit: None,
+            rate_limit_schedule: Vec::new(),
+            min_free_space_mb: 500,
+            language: crate::models::Language::ZhCn,
+            clipboard_watch: false,
+            clipboard_ignore: Vec::new(),
+            download_archive: None,
         };
         
         // 保存配置
@@ -207,6 +215,15 @@ mod tests {
             auto_check_update: true,
             concurrent_downloads: 3,
             youtube_cookie_browser: crate::models::BrowserType::None,
+            update_mirrors: Vec::new(),
+            proxy: None,
+            rate_limit: None,
+            rate_limit_schedule: Vec::new(),
+            min_free_space_mb: 500,
+            language: crate::models::Language::ZhCn,
+            clipboard_watch: false,
+            clipboard_ignore: Vec::new(),
+            download_archive: None,
         };
         
         let config2 = AppConfig {
@@ -215,6 +232,15 @@ mod tests {
             auto_check_update: false,
             concurrent_downloads: 5,
             youtube_cookie_browser: crate::models::BrowserType::None,
+            update_mirrors: Vec::new(),
+            proxy: None,
+            rate_limit: None,
+            rate_limit_schedule: Vec::new(),
+            min_free_space_mb: 500,
+            language: crate::models::Language::ZhCn,
+            clipboard_watch: false,
+            clipboard_ignore: Vec::new(),
+            download_archive: None,
         };
         
         // 保存第一个配置
@@ -243,6 +269,15 @@ mod tests {
             auto_check_update: true,
             concurrent_downloads: 3,
             youtube_cookie_browser: crate::models::BrowserType::None,
+            update_mirrors: Vec::new(),
+            proxy: None,
+            rate_limit: None,
+            rate_limit_schedule: Vec::new(),
+            min_free_space_mb: 500,
+            language: crate::models::Language::ZhCn,
+            clipboard_watch: false,
+            clipboard_ignore: Vec::new(),
+            download_archive: None,
         };
         
         service.save(&config).unwrap();
@@ -0,0 +1,165 @@
+// yt-dlp 下载存档（`--download-archive`）互通
+// 存档文件每行一条记录：`<提取器> <视频 ID>`，提取器名称为小写（如 `youtube dQw4w9WgXcQ`）
+
+use crate::models::HistoryItem;
+use crate::utils::url::normalize_url;
+use std::collections::HashSet;
+
+/// 历史记录对应的存档标识
+///
+/// 没有记录存档标识的旧数据按链接推断（目前只支持 YouTube 视频）
+pub fn archive_id(item: &HistoryItem) -> Option<String> {
+    if let Some(id) = &item.archive_id {
+        return Some(id.clone());
+    }
+    let video_id = normalize_url(&item.url).ok()?.video_id?;
+    Some(format!("youtube {}", video_id))
+}
+
+/// 解析存档文件内容，返回去重后的存档标识（保持原有顺序）
+///
+/// 格式不正确的行会被忽略
+pub fn parse_archive(text: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    text.trim_start_matches('\u{feff}')
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some(extractor), Some(id), None) => {
+                    Some(format!("{} {}", extractor.to_lowercase(), id))
+                }
+                _ => None,
+            }
+        })
+        .filter(|entry| seen.insert(entry.clone()))
+        .collect()
+}
+
+/// 把历史记录导出为存档文件内容
+pub fn export_archive(items: &[HistoryItem]) -> String {
+    let mut seen = HashSet::new();
+    items
+        .iter()
+        .filter_map(archive_id)
+        .filter(|entry| seen.insert(entry.clone()))
+        .map(|entry| entry + "\n")
+        .collect()
+}
+
+/// 为历史记录中还没有的存档条目生成历史记录项
+///
+/// 导入的记录没有本地文件；YouTube 视频会生成对应的链接，以便下载前的重复检查
+pub fn history_from_archive(
+    entries: &[String],
+    existing: &[HistoryItem],
+    now: i64,
+) -> Vec<HistoryItem> {
+    let known: HashSet<String> = existing.iter().filter_map(archive_id).collect();
+
+    entries
+        .iter()
+        .filter(|entry| !known.contains(*entry))
+        .map(|entry| {
+            let (extractor, video_id) = entry.split_once(' ').unwrap_or(("", entry));
+            let url = if extractor == "youtube" {
+                format!("https://www.youtube.com/watch?v={}", video_id)
+            } else {
+                String::new()
+            };
+            HistoryItem {
+                id: format!("archive-{}-{}", extractor, video_id),
+                title: video_id.to_string(),
+                uploader: String::new(),
+                url,
+                resolution: String::new(),
+                file_path: String::new(),
+                file_size: 0,
+                downloaded_at: now,
+                file_exists: false,
+                archive_id: Some(entry.clone()),
+                format_id: String::new(),
+                duration: 0,
+                thumbnail: String::new(),
+                extractor: extractor.to_string(),
+                video_id: video_id.to_string(),
+                vcodec: String::new(),
+                acodec: String::new(),
+                thumbnail_path: None,
+                subtitle_files: Vec::new(),
+                tags: Vec::new(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(url: &str, archive_id: Option<&str>) -> HistoryItem {
+        HistoryItem {
+            id: "hist-1".to_string(),
+            title: "Video".to_string(),
+            uploader: String::new(),
+            url: url.to_string(),
+            resolution: "1080p".to_string(),
+            file_path: "/downloads/video.mp4".to_string(),
+            file_size: 1024,
+            downloaded_at: 1_700_000_000_000,
+            file_exists: true,
+            archive_id: archive_id.map(str::to_string),
+            format_id: String::new(),
+            duration: 0,
+            thumbnail: String::new(),
+            extractor: String::new(),
+            video_id: String::new(),
+            vcodec: String::new(),
+            acodec: String::new(),
+            thumbnail_path: None,
+            subtitle_files: Vec::new(),
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_archive() {
+        let text = "\u{feff}youtube dQw4w9WgXcQ\r\n\
+                    \n\
+                    Vimeo 123456\n\
+                    youtube dQw4w9WgXcQ\n\
+                    broken-line\n\
+                    too many parts here\n";
+        assert_eq!(parse_archive(text), vec!["youtube dQw4w9WgXcQ", "vimeo 123456"]);
+    }
+
+    #[test]
+    fn test_export_archive() {
+        let items = vec![
+            item("https://youtu.be/dQw4w9WgXcQ", None),
+            item("https://www.youtube.com/watch?v=dQw4w9WgXcQ", None),
+            item("https://www.bilibili.com/video/BV1xx", Some("bilibili BV1xx")),
+            item("https://example.com/video", None),
+        ];
+        assert_eq!(export_archive(&items), "youtube dQw4w9WgXcQ\nbilibili BV1xx\n");
+    }
+
+    #[test]
+    fn test_history_from_archive_skips_known_videos() {
+        let existing = vec![item("https://youtu.be/dQw4w9WgXcQ", None)];
+        let entries = parse_archive("youtube dQw4w9WgXcQ\nyoutube aaaaaaaaaaa\nvimeo 42\n");
+
+        let imported = history_from_archive(&entries, &existing, 1_700_000_000_000);
+        assert_eq!(imported.len(), 2);
+        assert_eq!(imported[0].url, "https://www.youtube.com/watch?v=aaaaaaaaaaa");
+        assert_eq!(imported[0].archive_id.as_deref(), Some("youtube aaaaaaaaaaa"));
+        assert_eq!(imported[0].video_id, "aaaaaaaaaaa");
+        assert_eq!(imported[1].url, "");
+        assert!(!imported[1].file_exists);
+
+        // 导入后再导出得到同样的条目
+        let mut all = existing.clone();
+        all.extend(imported);
+        assert_eq!(parse_archive(&export_archive(&all)), entries);
+    }
+}
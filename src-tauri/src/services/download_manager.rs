@@ -0,0 +1,751 @@
+// 下载任务管理器
+// 跟踪正在运行的下载任务，负责取消和限速调整
+
+use crate::error::AppError;
+use crate::models::{
+    AppConfig, DownloadTask, DuplicateAction, HistoryItem, ProgressData, TaskStatus,
+};
+use crate::services::task_output::{TaskLog, TaskOutputStore};
+use crate::services::{
+    DownloadOutcome, DownloadProbe, HistoryService, TaskSignal, YtdlpOptions, YtdlpService,
+};
+use chrono::Local;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use crate::utils::fs::{available_space, ensure_free_space, ensure_writable_dir};
+use crate::utils::i18n::{tr, Text};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// 单个任务的最大下载尝试次数（含首次）
+const MAX_DOWNLOAD_ATTEMPTS: u32 = 4;
+
+/// 重试退避的初始等待时间
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(5);
+
+/// 重试退避的最长等待时间
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(300);
+
+/// 文件大小未知时检查剩余空间的间隔
+const DISK_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 计算第 `attempt` 次尝试失败后的等待时间
+///
+/// 按指数退避，限流错误从更长的等待开始
+fn retry_delay(error: &AppError, attempt: u32) -> Duration {
+    let base = match error {
+        AppError::RateLimited => RETRY_BASE_DELAY * 6,
+        _ => RETRY_BASE_DELAY,
+    };
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+    base.saturating_mul(factor).min(RETRY_MAX_DELAY)
+}
+
+/// 单次下载尝试的运行选项
+///
+/// 用户明确选择再次下载时不使用下载存档，否则 yt-dlp 会直接跳过存档中已有的视频
+fn attempt_options(
+    base: &YtdlpOptions,
+    task: &DownloadTask,
+    rate_limit: Option<String>,
+    resume: bool,
+) -> YtdlpOptions {
+    YtdlpOptions {
+        rate_limit,
+        resume,
+        keep_both: task.on_duplicate == DuplicateAction::KeepBoth,
+        download_archive: base
+            .download_archive
+            .clone()
+            .filter(|_| task.on_duplicate == DuplicateAction::New),
+        ..base.clone()
+    }
+}
+
+/// 根据下载任务和下载前解析的信息生成历史记录
+///
+/// 无法解析视频信息时只记录任务本身的信息
+fn history_item(
+    task: &DownloadTask,
+    probe: Option<&DownloadProbe>,
+    file_path: &str,
+    file_size: u64,
+    downloaded_at: i64,
+) -> HistoryItem {
+    let (thumbnail_path, subtitle_files) = HistoryService::find_sidecars(Path::new(file_path));
+    let probe = probe.cloned().unwrap_or_default();
+    let archive_id = (!probe.extractor.is_empty() && !probe.video_id.is_empty())
+        .then(|| format!("{} {}", probe.extractor, probe.video_id));
+    let title = if task.title.is_empty() {
+        probe.title
+    } else {
+        task.title.clone()
+    };
+
+    HistoryItem {
+        id: format!("history-{}", uuid::Uuid::new_v4()),
+        title,
+        uploader: probe.uploader,
+        url: task.url.clone(),
+        resolution: probe.resolution,
+        file_path: file_path.to_string(),
+        file_size,
+        downloaded_at,
+        file_exists: Path::new(file_path).is_file(),
+        archive_id,
+        format_id: task.format_id.clone(),
+        duration: probe.duration,
+        thumbnail: probe.thumbnail,
+        extractor: probe.extractor,
+        video_id: probe.video_id,
+        vcodec: probe.vcodec,
+        acodec: probe.acodec,
+        thumbnail_path,
+        subtitle_files,
+        tags: probe.tags,
+    }
+}
+
+/// 正在运行的下载任务
+struct RunningTask {
+    /// 视频 URL
+    url: String,
+    /// 控制信号发送端
+    control: watch::Sender<TaskSignal>,
+    /// 任务级限速（覆盖全局配置）
+    rate_limit_override: Option<String>,
+    /// 当前生效的限速
+    rate_limit: Option<String>,
+}
+
+/// 下载任务管理器（作为 Tauri 全局状态）
+#[derive(Clone, Default)]
+pub struct DownloadManager {
+    tasks: Arc<Mutex<HashMap<String, RunningTask>>>,
+    /// 各任务的 yt-dlp 输出记录（任务结束后保留）
+    outputs: TaskOutputStore,
+}
+
+impl DownloadManager {
+    /// 启动下载任务
+    ///
+    /// 开始前确认保存目录可写，并按所选格式的大小检查目标磁盘空间，大小未知时在下载过程中定期检查。
+    /// 限速在启动时按配置的时段规则计算，任务级限速优先。
+    /// 可重试的错误按指数退避自动重试，重试时续传已下载的部分。
+    /// 下载完成后写入历史记录，窗口未打开或正在刷新时也不会遗漏。
+    /// 下载在后台执行，通过 download-progress / download-retry / download-complete / download-error
+    /// 事件通知前端，返回的句柄在任务结束时给出最终状态和尝试次数
+    pub fn start(
+        &self,
+        app_handle: AppHandle,
+        service: YtdlpService,
+        options: YtdlpOptions,
+        mut task: DownloadTask,
+        history: HistoryService,
+        config: &AppConfig,
+    ) -> Result<JoinHandle<DownloadTask>, AppError> {
+        // 启动 yt-dlp 前确认保存目录可用
+        ensure_writable_dir(Path::new(&task.save_path))?;
+
+        let control = self.register(&task.id, &task.url, task.rate_limit.clone(), config)?;
+        let manager = self.clone();
+        let min_free_space = config.min_free_space();
+        let output = self.outputs.open(&task.id);
+
+        let handle = tokio::spawn(async move {
+            let service = service.with_options(options).with_output(output);
+            let save_dir = PathBuf::from(&task.save_path);
+
+            let mut probe = None;
+            let result = match Self::check_disk_space(&service, &task, min_free_space).await {
+                Err(e) => Err(e),
+                Ok(probed) => {
+                    probe = probed;
+                    // 大小未知时定期检查剩余空间
+                    let monitor = probe.as_ref().and_then(|probe| probe.size).is_none().then(|| {
+                        Self::spawn_space_monitor(control.clone(), save_dir, min_free_space)
+                    });
+                    let result = manager
+                        .run_attempts(&app_handle, &service, &mut task, &control)
+                        .await;
+                    if let Some(monitor) = monitor {
+                        monitor.abort();
+                    }
+                    result
+                }
+            };
+
+            manager.unregister(&task.id);
+
+            // 下载过程中空间不足按失败处理
+            let result = match result {
+                Ok(DownloadOutcome::Interrupted(TaskSignal::DiskFull)) => {
+                    Err(AppError::InsufficientSpace)
+                }
+                other => other,
+            };
+
+            task.status = match result {
+                Ok(DownloadOutcome::Completed(file_path)) => {
+                    log::info!(task_id = task.id.as_str(); "[download_manager] 下载成功: {}", file_path);
+
+                    // 获取文件大小
+                    let file_size = std::fs::metadata(&file_path)
+                        .map(|m| m.len())
+                        .unwrap_or(0);
+
+                    // 先写入历史记录，前端收到完成事件时即可读取
+                    let item = history_item(
+                        &task,
+                        probe.as_ref(),
+                        &file_path,
+                        file_size,
+                        chrono::Utc::now().timestamp_millis(),
+                    );
+                    let item = match history.save(item.clone()) {
+                        Ok(()) => Some(item),
+                        Err(e) => {
+                            log::error!(task_id = task.id.as_str(); "[download_manager] 保存历史记录失败: {:?}", e);
+                            None
+                        }
+                    };
+
+                    // 发送下载完成事件
+                    let _ = app_handle.emit_all(
+                        "download-complete",
+                        serde_json::json!({
+                            "taskId": task.id,
+                            "filePath": file_path,
+                            "fileSize": file_size,
+                            "attempts": task.attempts,
+                            "historyItem": item,
+                        }),
+                    );
+                    TaskStatus::Completed
+                }
+                Ok(DownloadOutcome::Skipped) => {
+                    log::info!(task_id = task.id.as_str(); "[download_manager] 下载存档中已有该视频，已跳过");
+                    let _ = app_handle.emit_all(
+                        "download-skipped",
+                        serde_json::json!({
+                            "taskId": task.id,
+                            "attempts": task.attempts,
+                        }),
+                    );
+                    TaskStatus::Skipped
+                }
+                Ok(DownloadOutcome::Interrupted(_)) => {
+                    log::info!(task_id = task.id.as_str(); "[download_manager] 任务已取消");
+                    TaskStatus::Cancelled
+                }
+                Err(e) => {
+                    log::error!(task_id = task.id.as_str(); "[download_manager] 下载失败: {:?}", e);
+                    // 发送下载错误事件
+                    let _ = app_handle.emit_all(
+                        "download-error",
+                        serde_json::json!({
+                            "taskId": task.id,
+                            "error": e.user_message(),
+                            "errorType": e.code(),
+                            "attempts": task.attempts,
+                        }),
+                    );
+                    TaskStatus::Failed
+                }
+            };
+            task
+        });
+
+        Ok(handle)
+    }
+
+    /// 执行下载，处理限速调整后的重启和失败后的自动重试
+    async fn run_attempts(
+        &self,
+        app_handle: &AppHandle,
+        service: &YtdlpService,
+        task: &mut DownloadTask,
+        control: &watch::Sender<TaskSignal>,
+    ) -> Result<DownloadOutcome, AppError> {
+        let mut resume = false;
+        task.attempts = 1;
+
+        loop {
+            // 调整限速后重置为运行状态；已取消或空间不足的任务不再启动
+            control.send_if_modified(|signal| {
+                if *signal == TaskSignal::Restart {
+                    *signal = TaskSignal::Run;
+                    true
+                } else {
+                    false
+                }
+            });
+            let mut receiver = control.subscribe();
+            let signal = receiver.borrow().clone();
+            if signal != TaskSignal::Run {
+                return Ok(DownloadOutcome::Interrupted(signal));
+            }
+
+            let rate_limit = self.current_rate_limit(&task.id);
+            log::debug!(
+                task_id = task.id.as_str();
+                "[download_manager] 启动任务，限速: {:?}，续传: {}，第 {} 次尝试",
+                rate_limit, resume, task.attempts
+            );
+            let service = service
+                .clone()
+                .with_options(attempt_options(service.options(), task, rate_limit, resume));
+
+            // 创建进度回调函数
+            let progress_callback = {
+                let app_handle = app_handle.clone();
+                move |progress: ProgressData| {
+                    // 通过 Tauri 事件系统发送进度更新
+                    let _ = app_handle.emit_all("download-progress", progress);
+                }
+            };
+
+            let outcome = service
+                .download_video(
+                    task.id.clone(),
+                    task.url.clone(),
+                    task.format_id.clone(),
+                    task.save_path.clone(),
+                    progress_callback,
+                    receiver.clone(),
+                )
+                .await;
+
+            match outcome {
+                // 限速已调整，使用 --continue 续传
+                Ok(DownloadOutcome::Interrupted(TaskSignal::Restart)) => resume = true,
+                Err(e) if e.is_retryable() && task.attempts < MAX_DOWNLOAD_ATTEMPTS => {
+                    let delay = retry_delay(&e, task.attempts);
+                    log::warn!(
+                        task_id = task.id.as_str();
+                        "[download_manager] 下载失败，{} 秒后重试: {:?}",
+                        delay.as_secs(),
+                        e
+                    );
+                    let _ = app_handle.emit_all(
+                        "download-retry",
+                        serde_json::json!({
+                            "taskId": task.id,
+                            "attempt": task.attempts,
+                            "delaySecs": delay.as_secs(),
+                            "error": e.user_message(),
+                            "errorType": e.code(),
+                        }),
+                    );
+
+                    // 等待期间仍可取消
+                    tokio::select! {
+                        _ = tokio::time::sleep(delay) => {}
+                        signal = YtdlpService::wait_for_signal(&mut receiver) => {
+                            if signal != TaskSignal::Restart {
+                                return Ok(DownloadOutcome::Interrupted(signal));
+                            }
+                        }
+                    }
+
+                    task.attempts += 1;
+                    resume = true;
+                }
+                other => return other,
+            }
+        }
+    }
+
+    /// 按所选格式的估算大小检查目标磁盘空间
+    ///
+    /// 返回解析出的视频信息（同时用于记录历史），无法解析时返回 `None`
+    async fn check_disk_space(
+        service: &YtdlpService,
+        task: &DownloadTask,
+        min_free_space: u64,
+    ) -> Result<Option<DownloadProbe>, AppError> {
+        let probe = match service.probe_download(&task.url, &task.format_id).await {
+            Ok(probe) => Some(probe),
+            Err(e) => {
+                log::warn!(task_id = task.id.as_str(); "[download_manager] 无法估算文件大小: {:?}", e);
+                None
+            }
+        };
+
+        let size = probe.as_ref().and_then(|probe| probe.size).unwrap_or(0);
+        ensure_free_space(Path::new(&task.save_path), size, min_free_space)?;
+        Ok(probe)
+    }
+
+    /// 定期检查剩余空间，低于保留空间时通知任务终止
+    fn spawn_space_monitor(
+        control: watch::Sender<TaskSignal>,
+        save_dir: PathBuf,
+        min_free_space: u64,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(DISK_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                match available_space(&save_dir) {
+                    Ok(available) if available < min_free_space => {
+                        log::warn!("[download_manager] 磁盘剩余空间不足: {} 字节", available);
+                        control.send_if_modified(|signal| {
+                            if matches!(signal, TaskSignal::Run | TaskSignal::Restart) {
+                                *signal = TaskSignal::DiskFull;
+                                true
+                            } else {
+                                false
+                            }
+                        });
+                        return;
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::warn!("[download_manager] 无法获取剩余空间: {:?}", e),
+                }
+            }
+        })
+    }
+
+    /// 正在运行的任务数
+    pub fn running_count(&self) -> usize {
+        self.tasks.lock().unwrap().len()
+    }
+
+    /// 正在运行的任务的视频 URL
+    pub fn running_urls(&self) -> Vec<String> {
+        self.tasks
+            .lock()
+            .unwrap()
+            .values()
+            .map(|task| task.url.clone())
+            .collect()
+    }
+
+    /// 获取任务的 yt-dlp 输出记录
+    pub fn task_log(&self, task_id: &str) -> Option<TaskLog> {
+        self.outputs.get(task_id)
+    }
+
+    /// 取消下载任务
+    /// 需求：3.5
+    pub fn cancel(&self, task_id: &str) -> Result<(), AppError> {
+        let tasks = self.tasks.lock().unwrap();
+        let task = tasks
+            .get(task_id)
+            .ok_or_else(|| AppError::TaskNotFound(task_id.to_string()))?;
+        task.control.send_replace(TaskSignal::Cancel);
+        Ok(())
+    }
+
+    /// 调整单个任务的限速
+    ///
+    /// `rate_limit` 为空时恢复使用全局配置；生效限速变化时重启任务并续传
+    pub fn set_task_rate_limit(
+        &self,
+        task_id: &str,
+        rate_limit: Option<String>,
+        config: &AppConfig,
+    ) -> Result<(), AppError> {
+        let mut tasks = self.tasks.lock().unwrap();
+        let task = tasks
+            .get_mut(task_id)
+            .ok_or_else(|| AppError::TaskNotFound(task_id.to_string()))?;
+
+        task.rate_limit_override = rate_limit;
+        let effective = task
+            .rate_limit_override
+            .clone()
+            .or_else(|| config.effective_rate_limit(Local::now().time()));
+        Self::apply_rate_limit(task, effective);
+        Ok(())
+    }
+
+    /// 配置变更后重新应用全局限速
+    ///
+    /// 只影响未设置任务级限速的任务
+    pub fn reapply_rate_limits(&self, config: &AppConfig) {
+        let effective = config.effective_rate_limit(Local::now().time());
+        let mut tasks = self.tasks.lock().unwrap();
+        for task in tasks.values_mut() {
+            if task.rate_limit_override.is_none() {
+                Self::apply_rate_limit(task, effective.clone());
+            }
+        }
+    }
+
+    /// 更新生效限速，变化时通知任务重启
+    fn apply_rate_limit(task: &mut RunningTask, rate_limit: Option<String>) {
+        if task.rate_limit != rate_limit {
+            task.rate_limit = rate_limit;
+            // 已取消或空间不足的任务不再重启
+            task.control.send_if_modified(|signal| {
+                if matches!(signal, TaskSignal::Run | TaskSignal::Restart) {
+                    *signal = TaskSignal::Restart;
+                    true
+                } else {
+                    false
+                }
+            });
+        }
+    }
+
+    /// 登记任务，返回控制信号发送端
+    fn register(
+        &self,
+        task_id: &str,
+        url: &str,
+        rate_limit_override: Option<String>,
+        config: &AppConfig,
+    ) -> Result<watch::Sender<TaskSignal>, AppError> {
+        let mut tasks = self.tasks.lock().unwrap();
+        if tasks.contains_key(task_id) {
+            return Err(AppError::YtdlpError(tr(Text::TaskAlreadyRunning, &[])));
+        }
+
+        let rate_limit = rate_limit_override
+            .clone()
+            .or_else(|| config.effective_rate_limit(Local::now().time()));
+        let (control, _) = watch::channel(TaskSignal::Run);
+        tasks.insert(
+            task_id.to_string(),
+            RunningTask {
+                url: url.to_string(),
+                control: control.clone(),
+                rate_limit_override,
+                rate_limit,
+            },
+        );
+        Ok(control)
+    }
+
+    /// 移除已结束的任务
+    fn unregister(&self, task_id: &str) {
+        self.tasks.lock().unwrap().remove(task_id);
+    }
+
+    /// 获取任务当前生效的限速
+    fn current_rate_limit(&self, task_id: &str) -> Option<String> {
+        self.tasks
+            .lock()
+            .unwrap()
+            .get(task_id)
+            .and_then(|task| task.rate_limit.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(title: &str) -> DownloadTask {
+        DownloadTask {
+            id: "task-1".to_string(),
+            url: "https://www.youtube.com/watch?v=dQw4w9WgXcQ".to_string(),
+            title: title.to_string(),
+            format_id: "137+140".to_string(),
+            save_path: "/downloads".to_string(),
+            status: TaskStatus::Downloading,
+            created_at: 0,
+            rate_limit: None,
+            scheduled_at: None,
+            attempts: 1,
+            on_duplicate: DuplicateAction::default(),
+        }
+    }
+
+    #[test]
+    fn test_history_item_from_probe() {
+        let probe = DownloadProbe {
+            video_id: "dQw4w9WgXcQ".to_string(),
+            title: "Probed title".to_string(),
+            uploader: "Channel".to_string(),
+            duration: 212,
+            thumbnail: "https://i.ytimg.com/vi/dQw4w9WgXcQ/maxresdefault.jpg".to_string(),
+            extractor: "youtube".to_string(),
+            resolution: "1920x1080".to_string(),
+            vcodec: "avc1".to_string(),
+            acodec: "mp4a".to_string(),
+            tags: vec!["music".to_string()],
+            size: Some(100),
+        };
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("video.mp4");
+        for name in ["video.mp4", "video.webp", "video.en.vtt", "video.zh-Hans.srt"] {
+            std::fs::write(temp_dir.path().join(name), b"x").unwrap();
+        }
+
+        let item = history_item(&task(""), Some(&probe), file_path.to_str().unwrap(), 120, 1_000);
+        assert!(item.id.starts_with("history-"));
+        assert_eq!(item.title, "Probed title");
+        assert_eq!(item.resolution, "1920x1080");
+        assert_eq!(item.format_id, "137+140");
+        assert_eq!(item.file_size, 120);
+        assert_eq!(item.archive_id.as_deref(), Some("youtube dQw4w9WgXcQ"));
+        assert_eq!(item.video_id, "dQw4w9WgXcQ");
+        assert_eq!(item.tags, vec!["music"]);
+        assert_eq!(
+            item.thumbnail_path.map(PathBuf::from),
+            Some(temp_dir.path().join("video.webp"))
+        );
+        assert_eq!(item.subtitle_files.len(), 2);
+        assert!(item.file_exists);
+
+        // 保存目录不是下载的文件
+        let item = history_item(&task(""), Some(&probe), temp_dir.path().to_str().unwrap(), 0, 1_000);
+        assert!(!item.file_exists);
+
+        // 无法解析视频信息时使用任务信息
+        let item = history_item(&task("Task title"), None, "/downloads/video.mp4", 120, 1_000);
+        assert_eq!(item.title, "Task title");
+        assert_eq!(item.archive_id, None);
+        assert!(item.subtitle_files.is_empty());
+        assert!(!item.file_exists);
+    }
+
+    #[test]
+    fn test_explicit_redownload_ignores_archive() {
+        let base = YtdlpOptions {
+            download_archive: Some("/downloads/archive.txt".to_string()),
+            ..YtdlpOptions::default()
+        };
+        let mut task = task("Video");
+
+        let options = attempt_options(&base, &task, None, false);
+        assert_eq!(options.download_archive.as_deref(), Some("/downloads/archive.txt"));
+        assert!(!options.keep_both);
+
+        task.on_duplicate = DuplicateAction::Redownload;
+        assert_eq!(attempt_options(&base, &task, None, false).download_archive, None);
+
+        task.on_duplicate = DuplicateAction::KeepBoth;
+        let options = attempt_options(&base, &task, Some("1M".to_string()), true);
+        assert_eq!(options.download_archive, None);
+        assert!(options.keep_both && options.resume);
+        assert_eq!(options.rate_limit.as_deref(), Some("1M"));
+    }
+
+    #[test]
+    fn test_retry_delay_backoff() {
+        let network = AppError::NetworkInterrupted("timed out".to_string());
+        assert_eq!(retry_delay(&network, 1), Duration::from_secs(5));
+        assert_eq!(retry_delay(&network, 2), Duration::from_secs(10));
+        assert_eq!(retry_delay(&network, 3), Duration::from_secs(20));
+
+        // 限流错误等待更久，且不超过上限
+        assert_eq!(retry_delay(&AppError::RateLimited, 1), Duration::from_secs(30));
+        assert_eq!(retry_delay(&AppError::RateLimited, 10), RETRY_MAX_DELAY);
+    }
+
+    fn config_with_limit(rate_limit: Option<&str>) -> AppConfig {
+        AppConfig {
+            rate_limit: rate_limit.map(str::to_string),
+            ..AppConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_register_uses_override_or_config() {
+        let manager = DownloadManager::default();
+        let config = config_with_limit(Some("1M"));
+
+        manager.register("a", "https://example.com/a", None, &config).unwrap();
+        manager
+            .register("b", "https://example.com/b", Some("500K".to_string()), &config)
+            .unwrap();
+
+        assert_eq!(manager.current_rate_limit("a"), Some("1M".to_string()));
+        assert_eq!(manager.current_rate_limit("b"), Some("500K".to_string()));
+        assert_eq!(manager.running_count(), 2);
+
+        // 重复登记同一任务应失败
+        assert!(manager.register("a", "https://example.com/a", None, &config).is_err());
+    }
+
+    #[test]
+    fn test_set_task_rate_limit_restarts_task() {
+        let manager = DownloadManager::default();
+        let config = config_with_limit(None);
+        let control = manager.register("a", "https://example.com/a", None, &config).unwrap();
+        let receiver = control.subscribe();
+
+        manager
+            .set_task_rate_limit("a", Some("2M".to_string()), &config)
+            .unwrap();
+        assert_eq!(*receiver.borrow(), TaskSignal::Restart);
+        assert_eq!(manager.current_rate_limit("a"), Some("2M".to_string()));
+
+        assert!(matches!(
+            manager.set_task_rate_limit("missing", None, &config),
+            Err(AppError::TaskNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_reapply_skips_overridden_and_unchanged_tasks() {
+        let manager = DownloadManager::default();
+        let config = config_with_limit(Some("1M"));
+        let global = manager.register("global", "https://example.com/global", None, &config).unwrap().subscribe();
+        let overridden = manager
+            .register("overridden", "https://example.com/overridden", Some("500K".to_string()), &config)
+            .unwrap()
+            .subscribe();
+
+        // 限速未变化时不重启
+        manager.reapply_rate_limits(&config);
+        assert_eq!(*global.borrow(), TaskSignal::Run);
+
+        manager.reapply_rate_limits(&config_with_limit(Some("3M")));
+        assert_eq!(*global.borrow(), TaskSignal::Restart);
+        assert_eq!(manager.current_rate_limit("global"), Some("3M".to_string()));
+        assert_eq!(*overridden.borrow(), TaskSignal::Run);
+        assert_eq!(manager.current_rate_limit("overridden"), Some("500K".to_string()));
+    }
+
+    #[test]
+    fn test_cancelled_task_is_not_restarted() {
+        let manager = DownloadManager::default();
+        let config = config_with_limit(None);
+        let receiver = manager.register("a", "https://example.com/a", None, &config).unwrap().subscribe();
+
+        manager.cancel("a").unwrap();
+        manager
+            .set_task_rate_limit("a", Some("1M".to_string()), &config)
+            .unwrap();
+        assert_eq!(*receiver.borrow(), TaskSignal::Cancel);
+
+        manager.unregister("a");
+        assert!(matches!(manager.cancel("a"), Err(AppError::TaskNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_space_monitor_stops_task_when_disk_full() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let manager = DownloadManager::default();
+        let config = config_with_limit(None);
+        let control = manager.register("a", "https://example.com/a", None, &config).unwrap();
+        let mut receiver = control.subscribe();
+
+        // 保留空间超过磁盘容量，首次检查即判定空间不足
+        let monitor = DownloadManager::spawn_space_monitor(
+            control.clone(),
+            temp_dir.path().to_path_buf(),
+            u64::MAX,
+        );
+        assert_eq!(
+            YtdlpService::wait_for_signal(&mut receiver).await,
+            TaskSignal::DiskFull
+        );
+        monitor.await.unwrap();
+
+        // 空间不足的任务不会因调整限速而重启
+        manager
+            .set_task_rate_limit("a", Some("1M".to_string()), &config)
+            .unwrap();
+        assert_eq!(*receiver.borrow(), TaskSignal::DiskFull);
+    }
+}
@@ -0,0 +1,680 @@
+// 历史记录文件（JSON / CSV 导入导出，HTML 报告）
+// 旧版本 JSON 直接保存记录数组（版本 0），现在的文件为 `{"version": N, "items": [...]}`。
+// 读取时逐条升级到当前版本；缺少或类型不符的字段使用默认值，不因个别字段不匹配而整体失败。
+// CSV 与 JSON 使用相同的字段名作为表头，读取时走同一套宽松的字段解析。
+// 写到一半被中断的 JSON 可以用 `salvage_history_json` 恢复前面完整的记录
+
+use crate::error::AppError;
+use crate::models::HistoryItem;
+use crate::utils::fs::format_size;
+use crate::utils::i18n::{tr, Text};
+use chrono::{DateTime, Local, TimeZone};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::path::Path;
+
+/// 当前的文件格式版本
+pub const HISTORY_FILE_VERSION: u64 = 1;
+
+/// CSV 的列，与 JSON 字段名一致
+const CSV_COLUMNS: [&str; 20] = [
+    "id",
+    "title",
+    "uploader",
+    "url",
+    "resolution",
+    "formatId",
+    "filePath",
+    "fileSize",
+    "downloadedAt",
+    "fileExists",
+    "archiveId",
+    "duration",
+    "thumbnail",
+    "extractor",
+    "videoId",
+    "vcodec",
+    "acodec",
+    "thumbnailPath",
+    "subtitleFiles",
+    "tags",
+];
+
+/// CSV 中列表字段的分隔符
+const CSV_LIST_SEPARATOR: char = ';';
+
+/// 表格软件会当作公式执行的单元格开头
+const CSV_FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// 从损坏的 JSON 中恢复的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct SalvagedHistory {
+    /// 恢复的记录
+    pub items: Vec<HistoryItem>,
+    /// 估计丢失的记录数
+    pub lost: usize,
+}
+
+#[derive(Serialize)]
+struct HistoryFile<'a> {
+    version: u64,
+    items: &'a [HistoryItem],
+}
+
+/// 以当前版本导出 JSON
+pub fn history_to_json(items: &[HistoryItem]) -> Result<String, AppError> {
+    Ok(serde_json::to_string_pretty(&HistoryFile {
+        version: HISTORY_FILE_VERSION,
+        items,
+    })?)
+}
+
+/// 读取任意版本的历史记录 JSON
+///
+/// 只有整个文件不是合法 JSON 或结构无法识别时返回错误；无法识别的单条记录会被跳过
+pub fn parse_history_json(text: &str) -> Result<Vec<HistoryItem>, AppError> {
+    let value: Value = serde_json::from_str(text.trim_start_matches('\u{feff}'))?;
+    let (version, items) = match value {
+        Value::Array(items) => (0, items),
+        Value::Object(mut file) => {
+            let version = file.get("version").and_then(Value::as_u64).unwrap_or(0);
+            match file.remove("items") {
+                Some(Value::Array(items)) => (version, items),
+                _ => return Err(invalid_format(&tr(Text::HistoryItemsMissing, &[]))),
+            }
+        }
+        _ => return Err(invalid_format(&tr(Text::HistoryFormatUnknown, &[]))),
+    };
+    if version > HISTORY_FILE_VERSION {
+        log::warn!(
+            "[history_file] 文件版本 {} 高于当前支持的版本 {}，按当前版本读取",
+            version,
+            HISTORY_FILE_VERSION
+        );
+    }
+
+    Ok(items_from_values(items, version))
+}
+
+/// 尽量读取损坏（如写入被中断而截断）的历史记录 JSON
+///
+/// 从记录数组开头逐条解析，直到第一条不完整的记录为止。
+/// 丢失的条数按剩余部分中的 `"id"` 字段估计，至少为 1
+pub fn salvage_history_json(text: &str) -> SalvagedHistory {
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    let (version, start) = if text.starts_with('[') {
+        (0, Some(1))
+    } else {
+        // 带版本号的文件中 version 写在 items 前面，找不到时按当前版本读取
+        let version = field_start(text, "version")
+            .and_then(|start| {
+                let digits: String = text[start..].chars().take_while(char::is_ascii_digit).collect();
+                digits.parse().ok()
+            })
+            .unwrap_or(HISTORY_FILE_VERSION);
+        let start = field_start(text, "items")
+            .filter(|&start| text[start..].starts_with('['))
+            .map(|start| start + 1);
+        (version, start)
+    };
+    let Some(mut offset) = start else {
+        return SalvagedHistory { items: Vec::new(), lost: 1 };
+    };
+
+    let mut values = Vec::new();
+    let complete = loop {
+        offset += whitespace_len(&text[offset..]);
+        if text[offset..].starts_with(']') {
+            break true;
+        }
+        let mut stream = serde_json::Deserializer::from_str(&text[offset..]).into_iter::<Value>();
+        match stream.next() {
+            Some(Ok(value)) => {
+                let end = offset + stream.byte_offset();
+                offset = end + whitespace_len(&text[end..]);
+                values.push(value);
+            }
+            _ => break false,
+        }
+        if text[offset..].starts_with(',') {
+            offset += 1;
+        } else {
+            break text[offset..].starts_with(']');
+        }
+    };
+
+    let lost = if complete {
+        0
+    } else {
+        text[offset..].matches("\"id\"").count().max(1)
+    };
+    SalvagedHistory {
+        items: items_from_values(values, version),
+        lost,
+    }
+}
+
+/// 逐条升级并构造记录，跳过无法识别的条目
+fn items_from_values(values: Vec<Value>, version: u64) -> Vec<HistoryItem> {
+    values
+        .into_iter()
+        .filter_map(|item| match item {
+            Value::Object(mut fields) => {
+                upgrade(&mut fields, version);
+                item_from_fields(&fields)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// 顶层对象中 `"key":` 之后的值的起始位置
+fn field_start(text: &str, key: &str) -> Option<usize> {
+    let pattern = format!("\"{}\"", key);
+    let after_key = text.find(&pattern)? + pattern.len();
+    let rest = &text[after_key..];
+    let colon = whitespace_len(rest);
+    if !rest[colon..].starts_with(':') {
+        return None;
+    }
+    let value = colon + 1;
+    Some(after_key + value + whitespace_len(&rest[value..]))
+}
+
+fn whitespace_len(text: &str) -> usize {
+    text.len() - text.trim_start().len()
+}
+
+/// 导出 CSV
+///
+/// 带 UTF-8 BOM 以便 Excel 正确识别中文；下载时间写为本地时区的 RFC 3339，列表字段以 `;` 连接。
+/// 标题等来自网站的内容可能以 `=` 等开头，写入时加上 `'` 避免被表格软件当作公式执行
+pub fn history_to_csv(items: &[HistoryItem]) -> Result<String, AppError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(CSV_COLUMNS).map_err(csv_error)?;
+    for item in items {
+        let separator = CSV_LIST_SEPARATOR.to_string();
+        writer
+            .write_record([
+                item.id.clone(),
+                item.title.clone(),
+                item.uploader.clone(),
+                item.url.clone(),
+                item.resolution.clone(),
+                item.format_id.clone(),
+                item.file_path.clone(),
+                item.file_size.to_string(),
+                local_time(item.downloaded_at)
+                    .map(|time| time.to_rfc3339())
+                    .unwrap_or_default(),
+                item.file_exists.to_string(),
+                item.archive_id.clone().unwrap_or_default(),
+                item.duration.to_string(),
+                item.thumbnail.clone(),
+                item.extractor.clone(),
+                item.video_id.clone(),
+                item.vcodec.clone(),
+                item.acodec.clone(),
+                item.thumbnail_path.clone().unwrap_or_default(),
+                item.subtitle_files.join(&separator),
+                item.tags.join(&separator),
+            ]
+            .map(|cell| escape_formula(&cell)))
+            .map_err(csv_error)?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| AppError::FileSystemError(e.into_error()))?;
+    Ok(format!("\u{feff}{}", String::from_utf8_lossy(&bytes)))
+}
+
+/// 读取 CSV
+///
+/// 按表头匹配列，缺少的列使用默认值；下载时间可以是 RFC 3339 或毫秒时间戳
+pub fn parse_history_csv(content: &str) -> Result<Vec<HistoryItem>, AppError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(content.trim_start_matches('\u{feff}').as_bytes());
+    let headers = reader.headers().map_err(csv_error)?.clone();
+
+    let mut items = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(csv_error)?;
+        let mut fields: Map<String, Value> = headers
+            .iter()
+            .zip(record.iter())
+            .map(|(header, value)| {
+                (header.trim().to_string(), Value::String(unescape_formula(value).to_string()))
+            })
+            .collect();
+
+        let downloaded_at = text(&fields, "downloadedAt");
+        if let Ok(time) = DateTime::parse_from_rfc3339(downloaded_at.trim()) {
+            fields.insert("downloadedAt".to_string(), time.timestamp_millis().into());
+        }
+        for key in ["subtitleFiles", "tags"] {
+            let values: Vec<Value> = text(&fields, key)
+                .split(CSV_LIST_SEPARATOR)
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(|value| Value::String(value.to_string()))
+                .collect();
+            fields.insert(key.to_string(), Value::Array(values));
+        }
+        items.extend(item_from_fields(&fields));
+    }
+    Ok(items)
+}
+
+/// 生成静态 HTML 报告
+///
+/// 缩略图优先使用下载到本地的文件，没有时使用在线地址
+pub fn history_to_html(items: &[HistoryItem]) -> String {
+    let title = escape(&tr(Text::HistoryReportTitle, &[]));
+    let total_size = items.iter().map(|item| item.file_size).sum();
+    let summary = tr(
+        Text::HistoryReportSummary,
+        &[&items.len(), &format_size(total_size)],
+    );
+    let headers: String = [
+        Text::ReportColumnVideo,
+        Text::ReportColumnUploader,
+        Text::ReportColumnResolution,
+        Text::ReportColumnSize,
+        Text::ReportColumnDuration,
+        Text::ReportColumnDownloadedAt,
+        Text::ReportColumnFile,
+    ]
+    .into_iter()
+    .map(|column| format!("<th>{}</th>", escape(&tr(column, &[]))))
+    .collect();
+
+    let mut rows = String::new();
+    for item in items {
+        let thumbnail = item
+            .thumbnail_path
+            .as_deref()
+            .filter(|path| Path::new(path).is_file())
+            .map(file_url)
+            .unwrap_or_else(|| item.thumbnail.clone());
+        let thumbnail = if thumbnail.is_empty() {
+            String::new()
+        } else {
+            format!(r#"<img src="{}" loading="lazy" alt="">"#, escape(&thumbnail))
+        };
+        let video = if item.url.is_empty() {
+            escape(&item.title)
+        } else {
+            format!(
+                r#"<a href="{}">{}</a>"#,
+                escape(&item.url),
+                escape(&item.title)
+            )
+        };
+        let downloaded_at = local_time(item.downloaded_at)
+            .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        rows.push_str(&format!(
+            "<tr><td class=\"thumb\">{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td>\
+             <td>{}</td><td>{}</td><td class=\"file\">{}</td></tr>\n",
+            thumbnail,
+            video,
+            escape(&item.uploader),
+            escape(&item.resolution),
+            format_size(item.file_size),
+            format_duration(item.duration),
+            downloaded_at,
+            escape(&item.file_path),
+        ));
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: system-ui, sans-serif; margin: 24px; color: #222; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ border-bottom: 1px solid #ddd; padding: 6px 8px; text-align: left; vertical-align: middle; }}
+th {{ background: #f5f5f5; }}
+td.thumb img {{ width: 160px; border-radius: 4px; }}
+td.file {{ color: #666; font-size: 12px; word-break: break-all; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p>{summary}</p>
+<table>
+<thead><tr><th></th>{headers}</tr></thead>
+<tbody>
+{rows}</tbody>
+</table>
+</body>
+</html>
+"#,
+        summary = escape(&summary),
+    )
+}
+
+/// 把单条记录从 `version` 升级到当前版本
+fn upgrade(fields: &mut Map<String, Value>, version: u64) {
+    // 版本 0：格式 ID 记在 resolution 中
+    if version < 1 && text(fields, "formatId").is_empty() {
+        let resolution = text(fields, "resolution");
+        fields.insert("formatId".to_string(), Value::String(resolution));
+    }
+}
+
+/// 按字段宽松地构造历史记录，既没有链接也没有文件路径的记录无法使用，返回 `None`
+fn item_from_fields(fields: &Map<String, Value>) -> Option<HistoryItem> {
+    let url = text(fields, "url");
+    let file_path = text(fields, "filePath");
+    if url.is_empty() && file_path.is_empty() {
+        return None;
+    }
+
+    let id = match text(fields, "id") {
+        id if id.is_empty() => format!("history-{}", uuid::Uuid::new_v4()),
+        id => id,
+    };
+    let archive_id = Some(text(fields, "archiveId")).filter(|id| !id.is_empty());
+    let thumbnail_path = Some(text(fields, "thumbnailPath")).filter(|path| !path.is_empty());
+
+    Some(HistoryItem {
+        id,
+        title: text(fields, "title"),
+        uploader: text(fields, "uploader"),
+        url,
+        resolution: text(fields, "resolution"),
+        file_path,
+        file_size: number(fields, "fileSize").max(0) as u64,
+        downloaded_at: number(fields, "downloadedAt"),
+        file_exists: flag(fields, "fileExists"),
+        archive_id,
+        format_id: text(fields, "formatId"),
+        duration: number(fields, "duration").clamp(0, u32::MAX as i64) as u32,
+        thumbnail: text(fields, "thumbnail"),
+        extractor: text(fields, "extractor"),
+        video_id: text(fields, "videoId"),
+        vcodec: text(fields, "vcodec"),
+        acodec: text(fields, "acodec"),
+        thumbnail_path,
+        subtitle_files: list(fields, "subtitleFiles"),
+        tags: list(fields, "tags"),
+    })
+}
+
+fn text(fields: &Map<String, Value>, key: &str) -> String {
+    match fields.get(key) {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Number(n)) => n.to_string(),
+        Some(Value::Bool(b)) => b.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn number(fields: &Map<String, Value>, key: &str) -> i64 {
+    match fields.get(key) {
+        Some(Value::Number(n)) => n
+            .as_i64()
+            .or_else(|| n.as_f64().map(|f| f as i64))
+            .unwrap_or(0),
+        Some(Value::String(s)) => s
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .or_else(|| s.trim().parse::<f64>().ok().map(|f| f as i64))
+            .unwrap_or(0),
+        _ => 0,
+    }
+}
+
+fn flag(fields: &Map<String, Value>, key: &str) -> bool {
+    match fields.get(key) {
+        Some(Value::Bool(b)) => *b,
+        Some(Value::Number(n)) => n.as_f64().is_some_and(|f| f != 0.0),
+        Some(Value::String(s)) => matches!(s.trim(), "true" | "1"),
+        _ => false,
+    }
+}
+
+fn list(fields: &Map<String, Value>, key: &str) -> Vec<String> {
+    match fields.get(key) {
+        Some(Value::Array(values)) => values
+            .iter()
+            .filter_map(|value| value.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn local_time(millis: i64) -> Option<DateTime<Local>> {
+    Local.timestamp_millis_opt(millis).single()
+}
+
+fn format_duration(seconds: u32) -> String {
+    match seconds {
+        0 => String::new(),
+        s if s >= 3600 => format!("{}:{:02}:{:02}", s / 3600, s % 3600 / 60, s % 60),
+        s => format!("{}:{:02}", s / 60, s % 60),
+    }
+}
+
+/// 本地文件路径转为 `file://` 地址
+fn file_url(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let path = path
+        .replace('%', "%25")
+        .replace(' ', "%20")
+        .replace('#', "%23")
+        .replace('?', "%3F");
+    if path.starts_with('/') {
+        format!("file://{}", path)
+    } else {
+        format!("file:///{}", path)
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// 在可能被当作公式的单元格前加上 `'`
+///
+/// 已经以 `'` 开头、去掉 `'` 后像公式的内容也加上，读取时只去掉一个 `'`，保证往返一致
+fn escape_formula(cell: &str) -> String {
+    if cell.trim_start_matches('\'').starts_with(CSV_FORMULA_PREFIXES) {
+        format!("'{}", cell)
+    } else {
+        cell.to_string()
+    }
+}
+
+/// 去掉 `escape_formula` 加上的 `'`
+fn unescape_formula(cell: &str) -> &str {
+    match cell.strip_prefix('\'') {
+        Some(rest) if rest.trim_start_matches('\'').starts_with(CSV_FORMULA_PREFIXES) => rest,
+        _ => cell,
+    }
+}
+
+fn csv_error(e: csv::Error) -> AppError {
+    AppError::FileSystemError(e.into())
+}
+
+fn invalid_format(message: &str) -> AppError {
+    AppError::JsonError(serde_json::Error::io(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        message,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_array_with_mismatched_fields() {
+        let text = r#"[
+            {"id":"a","title":"A","url":"https://youtu.be/aaaaaaaaaaa","resolution":"137+140",
+             "filePath":"/downloads/a.mp4","fileSize":"2048","downloadedAt":1.7e12},
+            {"id":"b","title":42,"url":"https://vimeo.com/1","fileSize":null,"fileExists":1},
+            {"title":"no link or file"},
+            "not an object"
+        ]"#;
+
+        let items = parse_history_json(text).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].file_size, 2048);
+        assert_eq!(items[0].downloaded_at, 1_700_000_000_000);
+        assert_eq!(items[0].format_id, "137+140");
+        assert!(!items[0].file_exists);
+        assert_eq!(items[1].title, "42");
+        assert_eq!(items[1].file_size, 0);
+        assert!(items[1].file_exists);
+    }
+
+    #[test]
+    fn test_versioned_roundtrip() {
+        let mut items = parse_history_json(
+            r#"[{"id":"a","title":"A","url":"https://youtu.be/aaaaaaaaaaa","resolution":"1080p",
+                 "filePath":"/downloads/a.mp4","fileSize":1,"downloadedAt":1,"fileExists":true}]"#,
+        )
+        .unwrap();
+        items[0].format_id = "22".to_string();
+        items[0].tags = vec!["music".to_string()];
+        items[0].subtitle_files = vec!["/downloads/a.en.vtt".to_string()];
+
+        // 带版本号的文件原样读取
+        let json = history_to_json(&items).unwrap();
+        assert_eq!(parse_history_json(&json).unwrap(), items);
+    }
+
+    #[test]
+    fn test_salvage_truncated_json() {
+        let entry = |id: &str| {
+            format!(
+                r#"{{"id":"{0}","title":"{0}","url":"https://youtu.be/{0}","resolution":"720p","filePath":"/downloads/{0}.mp4"}}"#,
+                id
+            )
+        };
+        let full = format!("[{},{},{}]", entry("a"), entry("b"), entry("c"));
+
+        // 完整的文件不丢失记录
+        let salvaged = salvage_history_json(&full);
+        assert_eq!(salvaged.items, parse_history_json(&full).unwrap());
+        assert_eq!(salvaged.lost, 0);
+
+        // 截断在第三条记录中间
+        let truncated = &full[..full.len() - 20];
+        assert!(parse_history_json(truncated).is_err());
+        let salvaged = salvage_history_json(truncated);
+        let ids: Vec<&str> = salvaged.items.iter().map(|item| item.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b"]);
+        assert_eq!(salvaged.items[0].format_id, "720p");
+        assert_eq!(salvaged.lost, 1);
+
+        // 带版本号的文件，中间有一条损坏的记录
+        let versioned = format!(
+            r#"{{"version": 1, "items": [{}, {{"id":"x","title": tru}}, {}]}}"#,
+            entry("a"),
+            entry("c")
+        );
+        let salvaged = salvage_history_json(&versioned);
+        assert_eq!(salvaged.items.len(), 1);
+        assert_eq!(salvaged.items[0].format_id, "");
+        assert_eq!(salvaged.lost, 2);
+
+        // 无法识别的内容
+        assert_eq!(salvage_history_json("\0\0\0").lost, 1);
+        assert!(salvage_history_json("").items.is_empty());
+    }
+
+    #[test]
+    fn test_csv_roundtrip() {
+        let mut items = parse_history_json(
+            r#"{"version":1,"items":[
+                {"id":"a","title":"Title, with \"quotes\"\nand newline","uploader":"频道",
+                 "url":"https://youtu.be/aaaaaaaaaaa","resolution":"1080p","formatId":"137+140",
+                 "filePath":"/downloads/a.mp4","fileSize":2048,"downloadedAt":1700000000000,
+                 "fileExists":true,"archiveId":"youtube aaaaaaaaaaa","duration":75,
+                 "subtitleFiles":["/downloads/a.en.vtt","/downloads/a.zh.vtt"],"tags":["music"]},
+                {"id":"b","url":"https://vimeo.com/1","downloadedAt":1}
+            ]}"#,
+        )
+        .unwrap();
+        items[1].file_path = "C:\\Videos\\b.mkv".to_string();
+
+        let csv = history_to_csv(&items).unwrap();
+        assert!(csv.starts_with('\u{feff}'));
+        assert_eq!(parse_history_csv(&csv).unwrap(), items);
+
+        // 可能被当作公式的内容加上 `'`，读取时还原
+        let mut formulas = items[..1].to_vec();
+        formulas[0].title = "=HYPERLINK(\"https://evil.example\",\"click\")".to_string();
+        formulas[0].uploader = "'=already quoted".to_string();
+        formulas[0].tags = vec!["@mention".to_string(), "-1".to_string()];
+        formulas[0].vcodec = "'plain".to_string();
+        let csv = history_to_csv(&formulas).unwrap();
+        assert!(csv.contains("\"'=HYPERLINK("));
+        assert!(csv.contains(",''=already quoted,"));
+        assert!(csv.contains(",'@mention;-1\n") || csv.contains(",'@mention;-1\r\n"));
+        assert!(csv.contains(",'plain,"));
+        assert_eq!(parse_history_csv(&csv).unwrap(), formulas);
+
+        // 列顺序无关，缺少的列使用默认值，毫秒时间戳也能识别
+        let items = parse_history_csv("url,downloadedAt,id\nhttps://youtu.be/x,42,x\n,,\n").unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, "x");
+        assert_eq!(items[0].downloaded_at, 42);
+        assert!(items[0].tags.is_empty());
+    }
+
+    #[test]
+    fn test_html_report_is_escaped() {
+        let items = parse_history_json(
+            r#"[{"id":"a","title":"<script>alert(1)</script>","uploader":"Tom & Jerry",
+                 "url":"https://example.com/?a=1&b=\"2\"","resolution":"720p",
+                 "filePath":"/downloads/a.mp4","fileSize":1048576,"duration":3725,
+                 "thumbnail":"https://i.ytimg.com/vi/a/hq.jpg"}]"#,
+        )
+        .unwrap();
+
+        let html = history_to_html(&items);
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(html.contains("Tom &amp; Jerry"));
+        assert!(html.contains(r#"href="https://example.com/?a=1&amp;b=&quot;2&quot;""#));
+        assert!(html.contains(r#"src="https://i.ytimg.com/vi/a/hq.jpg""#));
+        assert!(html.contains("1.00 MB"));
+        assert!(html.contains("1:02:05"));
+        assert!(html.contains(&tr(Text::HistoryReportTitle, &[])));
+    }
+
+    #[test]
+    fn test_file_url() {
+        assert_eq!(file_url("/home/a b/c#1.jpg"), "file:///home/a%20b/c%231.jpg");
+        assert_eq!(file_url("C:\\Videos\\a.jpg"), "file:///C:/Videos/a.jpg");
+    }
+
+    #[test]
+    fn test_invalid_structure() {
+        assert!(parse_history_json("not json").is_err());
+        assert!(parse_history_json("42").is_err());
+        assert!(parse_history_json(r#"{"version":1}"#).is_err());
+        assert!(parse_history_json(r#"{"version":1,"items":[]}"#).unwrap().is_empty());
+    }
+}
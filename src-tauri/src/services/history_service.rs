@@ -1,23 +1,173 @@
 use crate::error::AppError;
-use crate::models::HistoryItem;
+use crate::models::{
+    DeleteResult, HistoryImportResult, HistoryItem, HistoryPage, HistoryQuery, HistoryRecovery,
+    HistorySort, MergeStrategy, RelinkReport,
+};
+use crate::services::history_file::{parse_history_json, salvage_history_json};
+use crate::utils::fs::{is_subtitle, is_thumbnail, list_files, remove_media_files, sidecar_files};
+use crate::utils::i18n::{tr, Text};
+use crate::utils::url::url_key;
+use rusqlite::types::Value;
+use rusqlite::{
+    params, params_from_iter, Connection, ErrorCode, OptionalExtension, Row, TransactionBehavior,
+};
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// 本进程中已初始化的数据库，后续创建服务时不再重复升级和检查
+static INITIALIZED: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// 数据库文件名
+const DB_FILE: &str = "history.db";
+
+/// 旧版本使用的 JSON 历史记录文件
+const LEGACY_JSON_FILE: &str = "history.json";
+
+/// 尚未告知用户的恢复结果文件
+const RECOVERY_FILE: &str = "history-recovery.json";
+
+/// 数据库快照目录
+const SNAPSHOT_DIR: &str = "history-snapshots";
+
+/// 保留的快照份数（每天最多一份）
+const SNAPSHOT_KEEP: usize = 7;
+
+/// 其他连接持有写锁时的等待时间
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 查询历史记录时读取的列（顺序与 `row_to_item` 一致）
+const COLUMNS: &str = "id, title, uploader, url, resolution, file_path, file_size, \
+                       downloaded_at, file_exists, archive_id, format_id, duration, thumbnail, \
+                       extractor, video_id, vcodec, acodec, thumbnail_path, subtitle_files, tags";
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS history (
+        seq INTEGER PRIMARY KEY,
+        id TEXT NOT NULL,
+        title TEXT NOT NULL,
+        uploader TEXT NOT NULL DEFAULT '',
+        url TEXT NOT NULL,
+        url_key TEXT NOT NULL,
+        resolution TEXT NOT NULL,
+        file_path TEXT NOT NULL,
+        file_size INTEGER NOT NULL,
+        downloaded_at INTEGER NOT NULL,
+        file_exists INTEGER NOT NULL,
+        archive_id TEXT
+    );
+    CREATE INDEX IF NOT EXISTS idx_history_id ON history(id);
+    CREATE INDEX IF NOT EXISTS idx_history_downloaded_at ON history(downloaded_at);
+    CREATE INDEX IF NOT EXISTS idx_history_url_key ON history(url_key);
+    CREATE INDEX IF NOT EXISTS idx_history_uploader ON history(uploader);
+";
+
+/// 数据库结构的升级步骤，第 N 项把 `PRAGMA user_version` 从 N 升级到 N + 1
+const MIGRATIONS: [&str; 3] = [
+    SCHEMA,
+    // 下载完成时由后端记录更多信息；旧版本把格式 ID 记在 resolution 中
+    "ALTER TABLE history ADD COLUMN format_id TEXT NOT NULL DEFAULT '';
+     ALTER TABLE history ADD COLUMN duration INTEGER NOT NULL DEFAULT 0;
+     ALTER TABLE history ADD COLUMN thumbnail TEXT NOT NULL DEFAULT '';
+     ALTER TABLE history ADD COLUMN extractor TEXT NOT NULL DEFAULT '';
+     UPDATE history SET format_id = resolution;",
+    // 更详细的视频信息；字幕文件和标签保存为 JSON 数组，视频 ID 从存档标识中补全
+    "ALTER TABLE history ADD COLUMN video_id TEXT NOT NULL DEFAULT '';
+     ALTER TABLE history ADD COLUMN vcodec TEXT NOT NULL DEFAULT '';
+     ALTER TABLE history ADD COLUMN acodec TEXT NOT NULL DEFAULT '';
+     ALTER TABLE history ADD COLUMN thumbnail_path TEXT;
+     ALTER TABLE history ADD COLUMN subtitle_files TEXT NOT NULL DEFAULT '[]';
+     ALTER TABLE history ADD COLUMN tags TEXT NOT NULL DEFAULT '[]';
+     UPDATE history SET video_id = substr(archive_id, instr(archive_id, ' ') + 1)
+     WHERE archive_id IS NOT NULL;",
+];
+
+/// 全文索引：trigram 分词支持中文和任意子串，由触发器与 history 表保持同步
+const SEARCH_SCHEMA: &str = "
+    CREATE VIRTUAL TABLE IF NOT EXISTS history_fts USING fts5(
+        title, uploader, url, content='history', content_rowid='seq', tokenize='trigram'
+    );
+    CREATE TRIGGER IF NOT EXISTS history_fts_insert AFTER INSERT ON history BEGIN
+        INSERT INTO history_fts(rowid, title, uploader, url)
+        VALUES (new.seq, new.title, new.uploader, new.url);
+    END;
+    CREATE TRIGGER IF NOT EXISTS history_fts_delete AFTER DELETE ON history BEGIN
+        INSERT INTO history_fts(history_fts, rowid, title, uploader, url)
+        VALUES ('delete', old.seq, old.title, old.uploader, old.url);
+    END;
+    CREATE TRIGGER IF NOT EXISTS history_fts_update AFTER UPDATE OF title, uploader, url ON history
+    BEGIN
+        INSERT INTO history_fts(history_fts, rowid, title, uploader, url)
+        VALUES ('delete', old.seq, old.title, old.uploader, old.url);
+        INSERT INTO history_fts(rowid, title, uploader, url)
+        VALUES (new.seq, new.title, new.uploader, new.url);
+    END;
+";
+
+/// trigram 分词只能匹配至少 3 个字符的关键词，更短的关键词使用 LIKE
+const MIN_FTS_TERM_CHARS: usize = 3;
+
+/// 每页默认条数
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// 每页最多条数
+const MAX_PAGE_SIZE: usize = 500;
 
 /// 历史记录管理服务
+///
+/// 历史记录保存在 SQLite 数据库中，`seq` 越大的记录越新。
+/// 初始化时自动导入旧版本的 `history.json`；每天保留一份数据库快照，数据库损坏时从最近的快照恢复
 pub struct HistoryService {
     history_path: PathBuf,
+    snapshot_dir: PathBuf,
 }
 
 impl HistoryService {
     /// 创建新的历史记录服务实例
     ///
+    /// 数据库在本进程中初始化后只构造服务，不访问数据库；尚未初始化时先调用 [`HistoryService::init`]
+    ///
     /// # Arguments
     /// * `data_dir` - 历史记录文件所在目录
     ///
     /// # Returns
     /// 历史记录服务实例
     pub fn new(data_dir: impl AsRef<Path>) -> Result<Self, AppError> {
-        let data_dir = data_dir.as_ref();
+        let service = Self::at(data_dir.as_ref());
+        let mut initialized = INITIALIZED.lock().unwrap_or_else(|e| e.into_inner());
+        if !initialized.contains(&service.history_path) {
+            service.initialize()?;
+            initialized.push(service.history_path.clone());
+        }
+        Ok(service)
+    }
+    
+    /// 初始化数据库并创建服务实例（应用启动时调用一次）
+    ///
+    /// 升级数据库结构，导入旧版本的 `history.json`，数据库损坏时从快照恢复，并保存当天的快照
+    pub fn init(data_dir: impl AsRef<Path>) -> Result<Self, AppError> {
+        let service = Self::at(data_dir.as_ref());
+        let mut initialized = INITIALIZED.lock().unwrap_or_else(|e| e.into_inner());
+        service.initialize()?;
+        if !initialized.contains(&service.history_path) {
+            initialized.push(service.history_path.clone());
+        }
+        Ok(service)
+    }
+    
+    fn at(data_dir: &Path) -> Self {
+        HistoryService {
+            history_path: data_dir.join(DB_FILE),
+            snapshot_dir: data_dir.join(SNAPSHOT_DIR),
+        }
+    }
+    
+    /// 升级数据库、处理损坏和旧文件，并保存当天的快照
+    fn initialize(&self) -> Result<(), AppError> {
+        let data_dir = self.history_path.parent().unwrap_or(Path::new("."));
         
         // 确保数据目录存在
         if !data_dir.exists() {
@@ -26,40 +176,551 @@ impl HistoryService {
             })?;
         }
         
-        let history_path = data_dir.join("history.json");
+        let mut conn = match self.open_and_migrate() {
+            Err(e) if is_corrupt(&e) => {
+                log::warn!("[history] 数据库已损坏: {}", e);
+                let backup_path = self.recover_from_corruption()?;
+                let conn = self.open_and_migrate()?;
+                let recovered: i64 =
+                    conn.query_row("SELECT COUNT(*) FROM history", [], |row| row.get(0))?;
+                self.add_recovery(HistoryRecovery {
+                    backup_path: backup_path.to_string_lossy().to_string(),
+                    recovered: recovered as usize,
+                    lost: None,
+                })?;
+                conn
+            }
+            result => result?,
+        };
+        if let Some(recovery) = Self::migrate_json(&mut conn, &data_dir.join(LEGACY_JSON_FILE))? {
+            self.add_recovery(recovery)?;
+        }
+        
+        let today = chrono::Local::now().format("%Y%m%d").to_string();
+        if let Err(e) = self.rotate_snapshots(&conn, &today) {
+            log::warn!("[history] 无法创建历史记录快照: {}", e);
+        }
+        Ok(())
+    }
+    
+    /// 打开数据库并升级到当前版本
+    fn open_and_migrate(&self) -> Result<Connection, AppError> {
+        let mut conn = self.open()?;
+        Self::migrate_schema(&mut conn)?;
+        Self::create_search_index(&conn)?;
+        Ok(conn)
+    }
+    
+    /// 从数据库损坏中恢复
+    ///
+    /// 备份损坏的数据库文件，并从最近的快照恢复（没有快照时重新创建）
+    ///
+    /// # Returns
+    /// 损坏的数据库文件的备份路径
+    fn recover_from_corruption(&self) -> Result<PathBuf, AppError> {
+        for suffix in ["", "-wal", "-shm"] {
+            let path = PathBuf::from(format!("{}{}", self.history_path.display(), suffix));
+            let backup_path = PathBuf::from(format!("{}.backup", path.display()));
+            match fs::rename(&path, &backup_path) {
+                Ok(()) => {}
+                // 其他实例可能已经处理过
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
         
-        Ok(HistoryService { history_path })
+        match self.snapshots()?.pop() {
+            Some(snapshot) => {
+                fs::copy(&snapshot, &self.history_path)?;
+                log::warn!(
+                    "[history] 已备份损坏的数据库，并从快照 {} 恢复",
+                    snapshot.display()
+                );
+            }
+            None => log::warn!("[history] 已备份损坏的数据库，没有可用的快照，历史记录已清空"),
+        }
+        Ok(PathBuf::from(format!("{}.backup", self.history_path.display())))
+    }
+    
+    /// 恢复结果文件路径
+    fn recovery_path(&self) -> PathBuf {
+        self.history_path.with_file_name(RECOVERY_FILE)
+    }
+    
+    /// 记录一次恢复结果，等待下次查询时告知用户
+    fn add_recovery(&self, recovery: HistoryRecovery) -> Result<(), AppError> {
+        let path = self.recovery_path();
+        let mut recoveries: Vec<HistoryRecovery> = fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        recoveries.push(recovery);
+        fs::write(&path, serde_json::to_vec_pretty(&recoveries)?)?;
+        Ok(())
+    }
+    
+    /// 取出尚未告知用户的恢复结果，取出后不再返回
+    pub fn take_recoveries(&self) -> Result<Vec<HistoryRecovery>, AppError> {
+        let path = self.recovery_path();
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        fs::remove_file(&path)?;
+        match serde_json::from_slice(&bytes) {
+            Ok(recoveries) => Ok(recoveries),
+            Err(e) => {
+                log::warn!("[history] 无法读取恢复结果 {}: {}", path.display(), e);
+                Ok(Vec::new())
+            }
+        }
+    }
+    
+    /// 保存当天的数据库快照并删除多余的旧快照
+    ///
+    /// 当天已有快照或没有记录时不保存
+    fn rotate_snapshots(&self, conn: &Connection, today: &str) -> Result<(), AppError> {
+        let snapshot = self.snapshot_dir.join(format!("history-{}.db", today));
+        if snapshot.exists() {
+            return Ok(());
+        }
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM history", [], |row| row.get(0))?;
+        if count == 0 {
+            return Ok(());
+        }
+        
+        // 先写到临时文件，多个实例同时保存时不会留下不完整的快照
+        fs::create_dir_all(&self.snapshot_dir)?;
+        let temp_path = self
+            .snapshot_dir
+            .join(format!("history-{}.db.{}.tmp", today, uuid::Uuid::new_v4()));
+        if let Err(e) = conn.execute("VACUUM INTO ?1", [temp_path.to_string_lossy()]) {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e.into());
+        }
+        fs::rename(&temp_path, &snapshot)?;
+        
+        let snapshots = self.snapshots()?;
+        for old in &snapshots[..snapshots.len().saturating_sub(SNAPSHOT_KEEP)] {
+            fs::remove_file(old)?;
+        }
+        Ok(())
+    }
+    
+    /// 已有的快照（从旧到新）
+    fn snapshots(&self) -> Result<Vec<PathBuf>, AppError> {
+        let entries = match fs::read_dir(&self.snapshot_dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut snapshots: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("history-") && name.ends_with(".db"))
+            })
+            .collect();
+        snapshots.sort();
+        Ok(snapshots)
+    }
+    
+    /// 打开数据库连接
+    fn open(&self) -> Result<Connection, AppError> {
+        let conn = Connection::open(&self.history_path)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        Ok(conn)
+    }
+    
+    /// 按 `user_version` 执行尚未执行的升级步骤
+    fn migrate_schema(conn: &mut Connection) -> Result<(), AppError> {
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let version: usize = tx.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > MIGRATIONS.len() {
+            log::warn!("[history] 数据库版本 {} 高于当前程序支持的版本 {}", version, MIGRATIONS.len());
+        }
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            tx.execute_batch(migration)?;
+            tx.pragma_update(None, "user_version", index + 1)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+    
+    /// 创建全文索引，已有记录的数据库首次创建时重建索引
+    fn create_search_index(conn: &Connection) -> Result<(), AppError> {
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = 'history_fts')",
+            [],
+            |row| row.get(0),
+        )?;
+        conn.execute_batch(SEARCH_SCHEMA)?;
+        if !exists {
+            conn.execute("INSERT INTO history_fts(history_fts) VALUES ('rebuild')", [])?;
+        }
+        Ok(())
+    }
+    
+    /// 导入旧版本的 JSON 历史记录
+    ///
+    /// 导入和重命名旧文件在同一个写事务中完成，多个实例同时启动时只会导入一次。
+    /// 文件损坏时导入能恢复的记录，并把原文件备份为 `history.json.backup`
+    ///
+    /// # Returns
+    /// 文件损坏时返回恢复结果
+    fn migrate_json(
+        conn: &mut Connection,
+        json_path: &Path,
+    ) -> Result<Option<HistoryRecovery>, AppError> {
+        if !json_path.exists() {
+            return Ok(None);
+        }
+        
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        // 获得写锁后再检查一次，其他实例可能已经完成导入
+        if !json_path.exists() {
+            return Ok(None);
+        }
+        
+        let bytes = fs::read(json_path)?;
+        let content = String::from_utf8_lossy(&bytes);
+        let (items, lost) = match parse_history_json(&content) {
+            Ok(items) => (items, None),
+            Err(e) => {
+                let salvaged = salvage_history_json(&content);
+                log::warn!(
+                    "[history] {} 已损坏: {}，恢复 {} 条记录，约 {} 条记录丢失",
+                    json_path.display(),
+                    e,
+                    salvaged.items.len(),
+                    salvaged.lost
+                );
+                (salvaged.items, Some(salvaged.lost))
+            }
+        };
+        
+        // JSON 中最新的记录在前面
+        let count = items.len() as i64;
+        for (index, item) in items.iter().enumerate() {
+            Self::insert(&tx, item, count - index as i64)?;
+        }
+        
+        let backup_extension = if lost.is_some() { "json.backup" } else { "json.migrated" };
+        let backup_path = json_path.with_extension(backup_extension);
+        fs::rename(json_path, &backup_path)?;
+        if let Err(e) = tx.commit() {
+            let _ = fs::rename(&backup_path, json_path);
+            return Err(e.into());
+        }
+        
+        log::info!("[history] 已从 {} 导入 {} 条历史记录", json_path.display(), count);
+        Ok(lost.map(|lost| HistoryRecovery {
+            backup_path: backup_path.to_string_lossy().to_string(),
+            recovered: count as usize,
+            lost: Some(lost),
+        }))
+    }
+    
+    /// 插入一条记录
+    fn insert(conn: &Connection, item: &HistoryItem, seq: i64) -> Result<(), AppError> {
+        conn.execute(
+            "INSERT INTO history (seq, id, title, uploader, url, url_key, resolution, file_path,
+                                  file_size, downloaded_at, file_exists, archive_id, format_id,
+                                  duration, thumbnail, extractor, video_id, vcodec, acodec,
+                                  thumbnail_path, subtitle_files, tags)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17,
+                     ?18, ?19, ?20, ?21, ?22)",
+            params![
+                seq,
+                item.id,
+                item.title,
+                item.uploader,
+                item.url,
+                url_key(&item.url),
+                item.resolution,
+                item.file_path,
+                item.file_size as i64,
+                item.downloaded_at,
+                item.file_exists,
+                item.archive_id,
+                item.format_id,
+                item.duration,
+                item.thumbnail,
+                item.extractor,
+                item.video_id,
+                item.vcodec,
+                item.acodec,
+                item.thumbnail_path,
+                serde_json::to_string(&item.subtitle_files)?,
+                serde_json::to_string(&item.tags)?,
+            ],
+        )?;
+        Ok(())
+    }
+    
+    fn row_to_item(row: &Row) -> rusqlite::Result<HistoryItem> {
+        Ok(HistoryItem {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            uploader: row.get(2)?,
+            url: row.get(3)?,
+            resolution: row.get(4)?,
+            file_path: row.get(5)?,
+            file_size: row.get::<_, i64>(6)? as u64,
+            downloaded_at: row.get(7)?,
+            file_exists: row.get(8)?,
+            archive_id: row.get(9)?,
+            format_id: row.get(10)?,
+            duration: row.get(11)?,
+            thumbnail: row.get(12)?,
+            extractor: row.get(13)?,
+            video_id: row.get(14)?,
+            vcodec: row.get(15)?,
+            acodec: row.get(16)?,
+            thumbnail_path: row.get(17)?,
+            subtitle_files: json_list(row, 18)?,
+            tags: json_list(row, 19)?,
+        })
+    }
+    
+    /// 读取所有记录（最新的在前面），文件存在性为上次检查的结果
+    pub fn list(&self) -> Result<Vec<HistoryItem>, AppError> {
+        let conn = self.open()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM history ORDER BY seq DESC",
+            COLUMNS
+        ))?;
+        let items = stmt
+            .query_map([], Self::row_to_item)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(items)
     }
     
     /// 加载历史记录
     ///
-    /// 如果历史记录文件不存在，返回空列表
+    /// 重新检查每个文件是否存在，并保存变化的检查结果
     ///
     /// # Returns
     /// 历史记录列表
     pub fn load(&self) -> Result<Vec<HistoryItem>, AppError> {
-        // 如果历史记录文件不存在，返回空列表
-        if !self.history_path.exists() {
-            return Ok(Vec::new());
+        let mut items = self.list()?;
+        self.check_files(&mut items)?;
+        Ok(items)
+    }
+    
+    /// 重新检查所有记录的文件是否存在，并保存变化的检查结果
+    ///
+    /// # Returns
+    /// 检查结果发生变化的记录数
+    pub fn refresh_file_exists(&self) -> Result<usize, AppError> {
+        let mut items = self.list()?;
+        self.check_files(&mut items)
+    }
+    
+    /// 重新检查文件是否存在，并保存变化的检查结果
+    ///
+    /// # Returns
+    /// 检查结果发生变化的记录数
+    fn check_files(&self, items: &mut [HistoryItem]) -> Result<usize, AppError> {
+        let mut changed = Vec::new();
+        for item in items.iter_mut() {
+            let exists = Path::new(&item.file_path).exists();
+            if item.file_exists != exists {
+                item.file_exists = exists;
+                changed.push((item.id.as_str(), exists));
+            }
+        }
+        
+        let count = changed.len();
+        if !changed.is_empty() {
+            let mut conn = self.open()?;
+            let tx = conn.transaction()?;
+            for (id, exists) in changed {
+                tx.execute(
+                    "UPDATE history SET file_exists = ?1 WHERE id = ?2",
+                    params![exists, id],
+                )?;
+            }
+            tx.commit()?;
+        }
+        Ok(count)
+    }
+    
+    /// 按条件查询一页历史记录
+    ///
+    /// 游标记录上一页最后一条的排序值和 `seq`，翻页期间插入新记录不会导致重复或遗漏。
+    /// 按文件存在性筛选时使用上次检查的结果，只重新检查返回的记录；
+    /// 需要最新结果时先调用 [`HistoryService::refresh_file_exists`]
+    pub fn query(&self, query: &HistoryQuery) -> Result<HistoryPage, AppError> {
+        let (mut conditions, mut args) = Self::filter_conditions(query);
+        let conn = self.open()?;
+        let total: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM history {}", where_clause(&conditions)),
+            params_from_iter(args.iter()),
+            |row| row.get(0),
+        )?;
+        
+        let (column, descending) = sort_spec(query.sort);
+        let (op, order) = if descending { ("<", "DESC") } else { (">", "ASC") };
+        if let Some(cursor) = &query.cursor {
+            let (seq, value) = parse_cursor(cursor)?;
+            conditions.push(format!(
+                "({column} {op} ? OR ({column} = ? AND seq {op} ?))"
+            ));
+            args.extend([value.clone(), value, Value::Integer(seq)]);
         }
         
-        // 读取历史记录文件
-        let content = fs::read_to_string(&self.history_path)?;
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {COLUMNS}, seq FROM history {} ORDER BY {column} {order}, seq {order} LIMIT {}",
+            where_clause(&conditions),
+            limit + 1
+        ))?;
+        let mut rows = stmt
+            .query_map(params_from_iter(args.iter()), |row| {
+                Ok((Self::row_to_item(row)?, row.get::<_, i64>("seq")?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
         
-        // 解析 JSON
-        let mut items: Vec<HistoryItem> = serde_json::from_str(&content)?;
+        let next_cursor = if rows.len() > limit {
+            rows.truncate(limit);
+            rows.last().map(|(item, seq)| cursor_for(query.sort, item, *seq))
+        } else {
+            None
+        };
+        let mut items: Vec<HistoryItem> = rows.into_iter().map(|(item, _)| item).collect();
+        self.check_files(&mut items)?;
         
-        // 更新文件存在性状态
-        for item in &mut items {
-            item.file_exists = Path::new(&item.file_path).exists();
+        Ok(HistoryPage {
+            items,
+            next_cursor,
+            total: total as usize,
+            recoveries: Vec::new(),
+        })
+    }
+    
+    /// 把查询条件转换为 SQL 条件和参数
+    fn filter_conditions(query: &HistoryQuery) -> (Vec<String>, Vec<Value>) {
+        let mut conditions = Vec::new();
+        let mut args = Vec::new();
+        
+        // 每个关键词都要匹配（标题、上传者或链接之一）
+        let terms = query.search.as_deref().unwrap_or_default().split_whitespace();
+        for term in terms {
+            if term.chars().count() >= MIN_FTS_TERM_CHARS {
+                conditions.push(
+                    "seq IN (SELECT rowid FROM history_fts WHERE history_fts MATCH ?)".to_string(),
+                );
+                args.push(Value::Text(format!("\"{}\"", term.replace('"', "\"\""))));
+            } else {
+                conditions.push(
+                    "(title LIKE ? ESCAPE '\\' OR uploader LIKE ? ESCAPE '\\' OR url LIKE ? ESCAPE '\\')"
+                        .to_string(),
+                );
+                let pattern = format!("%{}%", escape_like(term));
+                args.extend(std::iter::repeat_n(Value::Text(pattern), 3));
+            }
         }
         
-        Ok(items)
+        let mut push = |condition: &str, value: Value| {
+            conditions.push(condition.to_string());
+            args.push(value);
+        };
+        if let Some(from) = query.from {
+            push("downloaded_at >= ?", Value::Integer(from));
+        }
+        if let Some(to) = query.to {
+            push("downloaded_at <= ?", Value::Integer(to));
+        }
+        if let Some(resolution) = &query.resolution {
+            push("resolution = ?", Value::Text(resolution.clone()));
+        }
+        if let Some(file_exists) = query.file_exists {
+            push("file_exists = ?", Value::Integer(file_exists as i64));
+        }
+        if let Some(min_size) = query.min_size {
+            push("file_size >= ?", Value::Integer(min_size as i64));
+        }
+        if let Some(max_size) = query.max_size {
+            push("file_size <= ?", Value::Integer(max_size as i64));
+        }
+        
+        (conditions, args)
+    }
+    
+    /// 读取符合条件的全部记录（忽略分页参数），用于导出
+    pub fn query_all(&self, query: &HistoryQuery) -> Result<Vec<HistoryItem>, AppError> {
+        let mut query = HistoryQuery {
+            cursor: None,
+            limit: Some(MAX_PAGE_SIZE),
+            ..query.clone()
+        };
+        let mut items = Vec::new();
+        loop {
+            let page = self.query(&query)?;
+            items.extend(page.items);
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => return Ok(items),
+            }
+        }
+    }
+    
+    /// 导入历史记录（如从其他电脑导出的文件）
+    ///
+    /// 按记录 ID 判断是否已存在；新记录按原顺序追加到列表末尾，覆盖的记录保持原位置。
+    /// 文件存在性按本机重新检查
+    pub fn import(
+        &self,
+        items: Vec<HistoryItem>,
+        strategy: MergeStrategy,
+    ) -> Result<HistoryImportResult, AppError> {
+        let mut conn = self.open()?;
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        if strategy == MergeStrategy::Replace {
+            tx.execute("DELETE FROM history", [])?;
+        }
+        let mut next_seq: i64 = tx.query_row("SELECT COALESCE(MIN(seq), 1) FROM history", [], |row| {
+            row.get(0)
+        })?;
+        
+        let mut result = HistoryImportResult::default();
+        for mut item in items {
+            item.file_exists = !item.file_path.is_empty() && Path::new(&item.file_path).exists();
+            let existing: Option<i64> = tx
+                .query_row("SELECT seq FROM history WHERE id = ?1", [&item.id], |row| row.get(0))
+                .optional()?;
+            match existing {
+                Some(seq) if strategy == MergeStrategy::Overwrite => {
+                    tx.execute("DELETE FROM history WHERE id = ?1", [&item.id])?;
+                    Self::insert(&tx, &item, seq)?;
+                    result.updated += 1;
+                }
+                Some(_) => result.skipped += 1,
+                None => {
+                    next_seq -= 1;
+                    Self::insert(&tx, &item, next_seq)?;
+                    result.added += 1;
+                }
+            }
+        }
+        tx.commit()?;
+        
+        log::info!(
+            "[history] 导入历史记录：新增 {}，覆盖 {}，跳过 {}",
+            result.added,
+            result.updated,
+            result.skipped
+        );
+        Ok(result)
     }
     
     /// 保存历史记录项
     ///
-    /// 将新的历史记录项添加到列表中并保存
+    /// 作为最新的记录插入（单条语句，插入是原子的）
     ///
     /// # Arguments
     /// * `item` - 要保存的历史记录项
@@ -67,41 +728,330 @@ impl HistoryService {
     /// # Returns
     /// 成功或错误
     pub fn save(&self, item: HistoryItem) -> Result<(), AppError> {
-        // 加载现有历史记录
-        let mut items = self.load()?;
+        let mut conn = self.open()?;
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let max_seq: i64 = tx.query_row("SELECT COALESCE(MAX(seq), 0) FROM history", [], |row| {
+            row.get(0)
+        })?;
+        Self::insert(&tx, &item, max_seq + 1)?;
+        tx.commit()?;
+        Ok(())
+    }
+    
+    /// 在列表末尾追加较早的历史记录（如从下载存档导入）
+    pub fn append(&self, new_items: Vec<HistoryItem>) -> Result<(), AppError> {
+        let mut conn = self.open()?;
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let min_seq: i64 = tx.query_row("SELECT COALESCE(MIN(seq), 1) FROM history", [], |row| {
+            row.get(0)
+        })?;
+        for (index, item) in new_items.iter().enumerate() {
+            Self::insert(&tx, item, min_seq - 1 - index as i64)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+    
+    /// 查找同一视频、同一格式的最近一次下载
+    ///
+    /// 视频按规范化后的链接匹配（YouTube 的各种链接形式视为同一视频）
+    pub fn find_duplicate(&self, url: &str, format_id: &str) -> Result<Option<HistoryItem>, AppError> {
+        let conn = self.open()?;
+        let item = conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM history WHERE url_key = ?1 AND format_id = ?2
+                     ORDER BY seq DESC LIMIT 1",
+                    COLUMNS
+                ),
+                params![url_key(url), format_id],
+                Self::row_to_item,
+            )
+            .optional()?;
+        Ok(item.map(|mut item| {
+            item.file_exists = Path::new(&item.file_path).exists();
+            item
+        }))
+    }
+    
+    /// 链接对应的视频是否已在历史记录中
+    pub fn contains_url(&self, url: &str) -> Result<bool, AppError> {
+        let conn = self.open()?;
+        let exists = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM history WHERE url_key = ?1)",
+            params![url_key(url)],
+            |row| row.get(0),
+        )?;
+        Ok(exists)
+    }
+    
+    /// 所有记录的链接
+    pub fn urls(&self) -> Result<Vec<String>, AppError> {
+        let conn = self.open()?;
+        let mut stmt = conn.prepare("SELECT url FROM history")?;
+        let urls = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(urls)
+    }
+    
+    /// 按 ID 读取记录
+    #[allow(dead_code)]
+    pub fn get(&self, id: &str) -> Result<Option<HistoryItem>, AppError> {
+        let conn = self.open()?;
+        let item = conn
+            .query_row(
+                &format!("SELECT {} FROM history WHERE id = ?1", COLUMNS),
+                params![id],
+                Self::row_to_item,
+            )
+            .optional()?;
+        Ok(item)
+    }
+    
+    /// 删除指定的记录，可同时删除媒体文件及其附属文件
+    ///
+    /// 逐条返回结果。文件删除失败的记录会保留；其他记录仍在使用的文件
+    /// （如重新下载覆盖了同一文件）不会删除
+    pub fn delete(&self, ids: &[String], delete_files: bool) -> Result<Vec<DeleteResult>, AppError> {
+        let mut seen = HashSet::new();
+        let ids: Vec<&str> = ids.iter().map(String::as_str).filter(|id| seen.insert(*id)).collect();
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders = vec!["?"; ids.len()].join(", ");
+        
+        let mut conn = self.open()?;
+        let mut targets: HashMap<String, HistoryItem> = conn
+            .prepare(&format!("SELECT {COLUMNS} FROM history WHERE id IN ({placeholders})"))?
+            .query_map(params_from_iter(&ids), Self::row_to_item)?
+            .map(|item| item.map(|item| (item.id.clone(), item)))
+            .collect::<Result<_, _>>()?;
+        
+        let mut results = Vec::with_capacity(ids.len());
+        let mut deletable = HashSet::new();
+        {
+            let mut shared = conn.prepare(&format!(
+                "SELECT 1 FROM history WHERE file_path = ? AND id NOT IN ({placeholders})"
+            ))?;
+            for id in &ids {
+                let mut result = DeleteResult {
+                    id: id.to_string(),
+                    deleted: false,
+                    removed_files: Vec::new(),
+                    error: None,
+                };
+                match targets.remove(*id) {
+                    None => result.error = Some(tr(Text::HistoryItemNotFound, &[])),
+                    Some(item) if delete_files
+                        && !item.file_path.is_empty()
+                        && !shared.exists(params_from_iter(
+                            std::iter::once(item.file_path.as_str()).chain(ids.iter().copied()),
+                        ))? =>
+                    {
+                        match remove_media_files(Path::new(&item.file_path)) {
+                            Ok(removed) => {
+                                result.removed_files = removed
+                                    .iter()
+                                    .map(|path| path.to_string_lossy().to_string())
+                                    .collect();
+                                deletable.insert(*id);
+                            }
+                            Err(e) => {
+                                log::warn!("[history] 删除文件失败 {}: {}", item.file_path, e);
+                                result.error = Some(e.user_message());
+                            }
+                        }
+                    }
+                    Some(_) => {
+                        deletable.insert(*id);
+                    }
+                }
+                results.push(result);
+            }
+        }
         
-        // 添加新记录到列表开头（最新的在前面）
-        items.insert(0, item);
+        let tx = conn.transaction()?;
+        for id in &deletable {
+            tx.execute("DELETE FROM history WHERE id = ?1", params![id])?;
+        }
+        tx.commit()?;
         
-        // 保存整个列表
-        self.save_all(&items)
+        for result in results.iter_mut() {
+            result.deleted = deletable.contains(result.id.as_str());
+        }
+        Ok(results)
     }
     
-    /// 保存所有历史记录
+    /// 清理历史记录：删除文件已不存在的记录，和/或早于指定时间下载的记录
     ///
     /// # Arguments
-    /// * `items` - 历史记录列表
+    /// * `missing` - 是否删除文件已不存在的记录（先重新检查文件；没有文件路径的存档记录除外）
+    /// * `before` - 删除下载时间早于此时间戳（毫秒）的记录
     ///
     /// # Returns
-    /// 成功或错误
-    fn save_all(&self, items: &[HistoryItem]) -> Result<(), AppError> {
-        // 序列化为 JSON（格式化输出）
-        let json = serde_json::to_string_pretty(items)?;
+    /// 删除的记录数
+    pub fn prune(&self, missing: bool, before: Option<i64>) -> Result<usize, AppError> {
+        if missing {
+            self.refresh_file_exists()?;
+        }
         
-        // 使用原子写入：先写入临时文件，然后重命名
-        let temp_path = self.history_path.with_extension("json.tmp");
+        let mut conditions = Vec::new();
+        if missing {
+            // 从下载存档导入的记录没有文件路径，只用于避免重复下载，不算文件丢失
+            conditions.push("(file_exists = 0 AND file_path != '')");
+        }
+        if before.is_some() {
+            conditions.push("downloaded_at < ?1");
+        }
+        if conditions.is_empty() {
+            return Ok(0);
+        }
         
-        // 写入临时文件
-        fs::write(&temp_path, json)?;
+        let conn = self.open()?;
+        let sql = format!("DELETE FROM history WHERE {}", conditions.join(" OR "));
+        let count = match before {
+            Some(before) => conn.execute(&sql, params![before])?,
+            None => conn.execute(&sql, [])?,
+        };
+        Ok(count)
+    }
+    
+    /// 下载文件夹整体移动后，把 `old_root` 下的文件路径改到 `new_root` 下
+    ///
+    /// 按路径组件匹配（`/a/b` 不会匹配 `/a/bc`），同时重新检查文件是否存在
+    ///
+    /// # Returns
+    /// 修改的记录数
+    pub fn relocate(&self, old_root: &Path, new_root: &Path) -> Result<usize, AppError> {
+        if old_root.as_os_str().is_empty() {
+            return Ok(0);
+        }
         
-        // 原子重命名
-        fs::rename(&temp_path, &self.history_path).map_err(|e| {
-            // 如果重命名失败，清理临时文件
-            let _ = fs::remove_file(&temp_path);
-            AppError::FileSystemError(e)
-        })?;
+        let mut conn = self.open()?;
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let rows = {
+            let mut stmt = tx.prepare(
+                "SELECT seq, file_path, thumbnail_path, subtitle_files FROM history
+                 WHERE file_path != ''",
+            )?;
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                        json_list(row, 3)?,
+                    ))
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
         
-        Ok(())
+        let relocated = |path: &str| {
+            Path::new(path)
+                .strip_prefix(old_root)
+                .ok()
+                .map(|rest| new_root.join(rest))
+        };
+        let mut count = 0;
+        for (seq, file_path, thumbnail_path, subtitle_files) in rows {
+            let Some(new_path) = relocated(&file_path) else {
+                continue;
+            };
+            // 附属文件随媒体文件一起移动
+            let thumbnail_path = thumbnail_path.map(|path| match relocated(&path) {
+                Some(moved) => moved.to_string_lossy().to_string(),
+                None => path,
+            });
+            let subtitle_files: Vec<String> = subtitle_files
+                .into_iter()
+                .map(|path| match relocated(&path) {
+                    Some(moved) => moved.to_string_lossy().to_string(),
+                    None => path,
+                })
+                .collect();
+            tx.execute(
+                "UPDATE history SET file_path = ?1, file_exists = ?2, thumbnail_path = ?3,
+                                    subtitle_files = ?4
+                 WHERE seq = ?5",
+                params![
+                    new_path.to_string_lossy(),
+                    new_path.exists(),
+                    thumbnail_path,
+                    serde_json::to_string(&subtitle_files)?,
+                    seq
+                ],
+            )?;
+            count += 1;
+        }
+        tx.commit()?;
+        Ok(count)
+    }
+    
+    /// 在 `dir` 下查找文件已不存在的记录，按文件名和大小重新关联
+    ///
+    /// 记录的大小未知时只按文件名匹配；匹配到多个文件时无法确定，不做修改
+    pub fn relink(&self, dir: &Path) -> Result<RelinkReport, AppError> {
+        let missing: Vec<HistoryItem> = self
+            .load()?
+            .into_iter()
+            .filter(|item| !item.file_exists && !item.file_path.is_empty())
+            .collect();
+        if missing.is_empty() {
+            return Ok(RelinkReport::default());
+        }
+        
+        // 同一个键对应多个文件时记为 None
+        let mut by_name_and_size: HashMap<(OsString, u64), Option<PathBuf>> = HashMap::new();
+        let mut by_name: HashMap<OsString, Option<PathBuf>> = HashMap::new();
+        for (path, size) in list_files(dir) {
+            let Some(name) = path.file_name().map(|name| name.to_os_string()) else {
+                continue;
+            };
+            by_name_and_size
+                .entry((name.clone(), size))
+                .and_modify(|found| *found = None)
+                .or_insert_with(|| Some(path.clone()));
+            by_name
+                .entry(name)
+                .and_modify(|found| *found = None)
+                .or_insert(Some(path));
+        }
+        
+        let mut report = RelinkReport::default();
+        let mut conn = self.open()?;
+        let tx = conn.transaction()?;
+        for item in &missing {
+            let found = Path::new(&item.file_path).file_name().and_then(|name| {
+                let name = name.to_os_string();
+                if item.file_size == 0 {
+                    by_name.get(&name).cloned().flatten()
+                } else {
+                    by_name_and_size.get(&(name, item.file_size)).cloned().flatten()
+                }
+            });
+            match found {
+                Some(path) => {
+                    let (thumbnail_path, subtitle_files) = Self::find_sidecars(&path);
+                    tx.execute(
+                        "UPDATE history SET file_path = ?1, file_exists = 1, thumbnail_path = ?2,
+                                            subtitle_files = ?3
+                         WHERE id = ?4",
+                        params![
+                            path.to_string_lossy(),
+                            thumbnail_path,
+                            serde_json::to_string(&subtitle_files)?,
+                            item.id
+                        ],
+                    )?;
+                    report.relinked += 1;
+                }
+                None => report.missing += 1,
+            }
+        }
+        tx.commit()?;
+        Ok(report)
     }
     
     /// 清空历史记录（保留文件）
@@ -111,8 +1061,9 @@ impl HistoryService {
     /// # Returns
     /// 成功或错误
     pub fn clear(&self) -> Result<(), AppError> {
-        // 保存空列表
-        self.save_all(&[])
+        let conn = self.open()?;
+        conn.execute("DELETE FROM history", [])?;
+        Ok(())
     }
     
     /// 检查文件是否存在
@@ -127,13 +1078,91 @@ impl HistoryService {
         Path::new(file_path).exists()
     }
     
-    /// 获取历史记录文件路径
+    /// 查找媒体文件旁的缩略图和字幕文件
+    pub fn find_sidecars(file_path: &Path) -> (Option<String>, Vec<String>) {
+        let sidecars = sidecar_files(file_path);
+        let thumbnail_path = sidecars
+            .iter()
+            .find(|path| is_thumbnail(path))
+            .map(|path| path.to_string_lossy().to_string());
+        let subtitle_files = sidecars
+            .iter()
+            .filter(|path| is_subtitle(path))
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+        (thumbnail_path, subtitle_files)
+    }
+    
+    /// 获取历史记录数据库路径
     #[allow(dead_code)]
     pub fn history_path(&self) -> &Path {
         &self.history_path
     }
 }
 
+/// 是否为数据库文件损坏（而不是被占用等临时错误）
+fn is_corrupt(error: &AppError) -> bool {
+    matches!(
+        error,
+        AppError::DatabaseError(rusqlite::Error::SqliteFailure(e, _))
+            if matches!(e.code, ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase)
+    )
+}
+
+/// 读取保存为 JSON 数组的列
+fn json_list(row: &Row, index: usize) -> rusqlite::Result<Vec<String>> {
+    let text: String = row.get(index)?;
+    serde_json::from_str(&text).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+/// 排序列和是否降序
+fn sort_spec(sort: HistorySort) -> (&'static str, bool) {
+    match sort {
+        HistorySort::Newest => ("downloaded_at", true),
+        HistorySort::Oldest => ("downloaded_at", false),
+        HistorySort::Largest => ("file_size", true),
+        HistorySort::Smallest => ("file_size", false),
+        HistorySort::Title => ("title COLLATE NOCASE", false),
+    }
+}
+
+fn where_clause(conditions: &[String]) -> String {
+    if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    }
+}
+
+/// 转义 LIKE 模式中的通配符
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// 生成分页游标：`[seq, 排序值]` 的 JSON
+fn cursor_for(sort: HistorySort, item: &HistoryItem, seq: i64) -> String {
+    let value = match sort {
+        HistorySort::Newest | HistorySort::Oldest => serde_json::json!(item.downloaded_at),
+        HistorySort::Largest | HistorySort::Smallest => serde_json::json!(item.file_size),
+        HistorySort::Title => serde_json::json!(item.title),
+    };
+    serde_json::json!([seq, value]).to_string()
+}
+
+fn parse_cursor(cursor: &str) -> Result<(i64, Value), AppError> {
+    let (seq, value): (i64, serde_json::Value) = serde_json::from_str(cursor)?;
+    let value = match value {
+        serde_json::Value::Number(n) => Value::Integer(n.as_i64().unwrap_or_default()),
+        serde_json::Value::String(s) => Value::Text(s),
+        _ => Value::Null,
+    };
+    Ok((seq, value))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -146,12 +1175,24 @@ mod tests {
         HistoryItem {
             id: id.to_string(),
             title: title.to_string(),
+            uploader: String::new(),
             url: format!("https://youtube.com/watch?v={}", id),
             resolution: "1080p".to_string(),
             file_path: file_path.to_string(),
             file_size: 1024000,
             downloaded_at: 1234567890,
             file_exists: true,
+            archive_id: None,
+            format_id: "1080p".to_string(),
+            duration: 0,
+            thumbnail: String::new(),
+            extractor: String::new(),
+            video_id: String::new(),
+            vcodec: String::new(),
+            acodec: String::new(),
+            thumbnail_path: None,
+            subtitle_files: Vec::new(),
+            tags: Vec::new(),
         }
     }
     
@@ -162,7 +1203,7 @@ mod tests {
         
         assert_eq!(
             service.history_path(),
-            temp_dir.path().join("history.json")
+            temp_dir.path().join("history.db")
         );
     }
     
@@ -248,6 +1289,148 @@ mod tests {
         assert!(file_path.exists());
     }
     
+    #[test]
+    fn test_delete_items() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = HistoryService::new(temp_dir.path()).unwrap();
+        
+        let kept_path = temp_dir.path().join("kept.mp4");
+        let removed_path = temp_dir.path().join("removed.mp4");
+        for path in [&kept_path, &removed_path, &temp_dir.path().join("removed.en.vtt")] {
+            fs::write(path, b"x").unwrap();
+        }
+        service.save(create_test_item("kept", "Kept", kept_path.to_str().unwrap())).unwrap();
+        service.save(create_test_item("removed", "Removed", removed_path.to_str().unwrap())).unwrap();
+        service.save(create_test_item("other", "Other", "/nonexistent/other.mp4")).unwrap();
+        
+        // 只删除记录时文件保留
+        let results = service.delete(&["kept".to_string()], false).unwrap();
+        assert!(results[0].deleted);
+        assert!(results[0].removed_files.is_empty());
+        assert!(kept_path.exists());
+        
+        // 同时删除媒体文件和字幕；不存在的 ID 单独报告
+        let ids = vec!["removed".to_string(), "missing".to_string(), "other".to_string()];
+        let results = service.delete(&ids, true).unwrap();
+        assert!(results[0].deleted);
+        assert_eq!(results[0].removed_files.len(), 2);
+        assert!(!removed_path.exists());
+        assert!(!results[1].deleted);
+        assert!(results[1].error.is_some());
+        assert!(results[2].deleted);
+        assert!(service.list().unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_delete_keeps_shared_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = HistoryService::new(temp_dir.path()).unwrap();
+        let file_path = temp_dir.path().join("video.mp4");
+        fs::write(&file_path, b"x").unwrap();
+        
+        // 重新下载覆盖了同一文件，删除旧记录时不能删掉新记录的文件
+        service.save(create_test_item("old", "Video", file_path.to_str().unwrap())).unwrap();
+        service.save(create_test_item("new", "Video", file_path.to_str().unwrap())).unwrap();
+        let results = service.delete(&["old".to_string()], true).unwrap();
+        assert!(results[0].deleted);
+        assert!(results[0].removed_files.is_empty());
+        assert!(file_path.exists());
+        assert!(service.get("new").unwrap().is_some());
+    }
+    
+    #[test]
+    fn test_prune() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = HistoryService::new(temp_dir.path()).unwrap();
+        let file_path = temp_dir.path().join("video.mp4");
+        fs::write(&file_path, b"x").unwrap();
+        
+        let mut old = create_test_item("old", "Old", file_path.to_str().unwrap());
+        old.downloaded_at = 1_000;
+        let mut recent = create_test_item("recent", "Recent", file_path.to_str().unwrap());
+        recent.downloaded_at = 5_000;
+        service.save(old).unwrap();
+        service.save(recent).unwrap();
+        service.save(create_test_item("missing", "Missing", "/nonexistent/video.mp4")).unwrap();
+        
+        assert_eq!(service.prune(false, None).unwrap(), 0);
+        assert_eq!(service.prune(true, None).unwrap(), 1);
+        assert_eq!(service.prune(false, Some(2_000)).unwrap(), 1);
+        let ids: Vec<String> = service.list().unwrap().into_iter().map(|item| item.id).collect();
+        assert_eq!(ids, vec!["recent"]);
+        assert!(file_path.exists());
+    }
+    
+    #[test]
+    fn test_prune_missing_keeps_archive_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = HistoryService::new(temp_dir.path()).unwrap();
+        let mut archived = create_test_item("archived", "Archived", "");
+        archived.file_exists = false;
+        archived.archive_id = Some("youtube archived".to_string());
+        service.append(vec![archived]).unwrap();
+        service.save(create_test_item("missing", "Missing", "/nonexistent/video.mp4")).unwrap();
+        
+        assert_eq!(service.prune(true, None).unwrap(), 1);
+        let ids: Vec<String> = service.list().unwrap().into_iter().map(|item| item.id).collect();
+        assert_eq!(ids, vec!["archived"]);
+    }
+    
+    #[test]
+    fn test_relocate() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = HistoryService::new(temp_dir.path()).unwrap();
+        let old_root = temp_dir.path().join("Videos");
+        let new_root = temp_dir.path().join("Archive").join("Videos");
+        fs::create_dir_all(new_root.join("music")).unwrap();
+        fs::write(new_root.join("music").join("song.mp4"), b"x").unwrap();
+        
+        let moved = old_root.join("music").join("song.mp4");
+        let sibling = temp_dir.path().join("Videos2").join("clip.mp4");
+        let mut item = create_test_item("moved", "Song", moved.to_str().unwrap());
+        item.subtitle_files = vec![old_root.join("music").join("song.en.vtt").to_string_lossy().to_string()];
+        service.save(item).unwrap();
+        service.save(create_test_item("sibling", "Clip", sibling.to_str().unwrap())).unwrap();
+        
+        assert_eq!(service.relocate(&old_root, &new_root).unwrap(), 1);
+        let item = service.get("moved").unwrap().unwrap();
+        assert_eq!(Path::new(&item.file_path), new_root.join("music").join("song.mp4"));
+        assert!(item.file_exists);
+        assert_eq!(Path::new(&item.subtitle_files[0]), new_root.join("music").join("song.en.vtt"));
+        // 前缀相同但不在目录下的路径不修改
+        assert_eq!(Path::new(&service.get("sibling").unwrap().unwrap().file_path), sibling);
+    }
+    
+    #[test]
+    fn test_relink_by_name_and_size() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = HistoryService::new(temp_dir.path()).unwrap();
+        let scan_dir = temp_dir.path().join("disk");
+        fs::create_dir_all(scan_dir.join("a")).unwrap();
+        fs::create_dir_all(scan_dir.join("b")).unwrap();
+        fs::write(scan_dir.join("a").join("video.mp4"), b"12345").unwrap();
+        fs::write(scan_dir.join("b").join("video.mp4"), b"123").unwrap();
+        fs::write(scan_dir.join("a").join("dup.mp4"), b"1").unwrap();
+        fs::write(scan_dir.join("b").join("dup.mp4"), b"1").unwrap();
+        
+        let mut sized = create_test_item("sized", "Video", "/old/video.mp4");
+        sized.file_size = 3;
+        let mut ambiguous = create_test_item("ambiguous", "Dup", "/old/dup.mp4");
+        ambiguous.file_size = 1;
+        let mut gone = create_test_item("gone", "Gone", "/old/gone.mp4");
+        gone.file_size = 10;
+        for item in [sized, ambiguous, gone] {
+            service.save(item).unwrap();
+        }
+        
+        let report = service.relink(&scan_dir).unwrap();
+        assert_eq!(report, RelinkReport { relinked: 1, missing: 2 });
+        let item = service.get("sized").unwrap().unwrap();
+        assert_eq!(Path::new(&item.file_path), scan_dir.join("b").join("video.mp4"));
+        assert!(item.file_exists);
+        assert_eq!(service.get("ambiguous").unwrap().unwrap().file_path, "/old/dup.mp4");
+    }
+    
     #[test]
     fn test_file_exists_check() {
         let temp_dir = TempDir::new().unwrap();
@@ -287,46 +1470,445 @@ mod tests {
     }
     
     #[test]
-    fn test_history_file_format() {
+    fn test_find_duplicate() {
         let temp_dir = TempDir::new().unwrap();
         let service = HistoryService::new(temp_dir.path()).unwrap();
         
-        let item = create_test_item("test1", "Test Video", "/downloads/video.mp4");
+        let existing_file = temp_dir.path().join("old.mp4");
+        File::create(&existing_file).unwrap();
         
-        service.save(item).unwrap();
+        let mut old = create_test_item("dQw4w9WgXcQ", "Old", "/nonexistent/old.mp4");
+        old.id = "old".to_string();
+        let mut recent = create_test_item("dQw4w9WgXcQ", "Recent", existing_file.to_str().unwrap());
+        recent.id = "recent".to_string();
+        service.save(old).unwrap();
+        service.save(recent).unwrap();
         
-        // 读取文件内容验证格式
-        let content = fs::read_to_string(service.history_path()).unwrap();
+        // 不同的链接形式匹配到最近一次下载
+        let found = service
+            .find_duplicate("https://youtu.be/dQw4w9WgXcQ?si=share", "1080p")
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, "recent");
+        assert!(found.file_exists);
         
-        // 验证 camelCase 格式
-        assert!(content.contains("\"filePath\""));
-        assert!(content.contains("\"fileSize\""));
-        assert!(content.contains("\"downloadedAt\""));
-        assert!(content.contains("\"fileExists\""));
+        // 格式或视频不同时不算重复
+        assert!(service.find_duplicate("https://youtu.be/dQw4w9WgXcQ", "720p").unwrap().is_none());
+        assert!(service.find_duplicate("https://youtu.be/aaaaaaaaaaa", "1080p").unwrap().is_none());
     }
     
     #[test]
-    fn test_atomic_write() {
+    fn test_append_keeps_existing_order() {
         let temp_dir = TempDir::new().unwrap();
         let service = HistoryService::new(temp_dir.path()).unwrap();
         
-        let item1 = create_test_item("test1", "Video 1", "/downloads/video1.mp4");
-        let item2 = create_test_item("test2", "Video 2", "/downloads/video2.mp4");
+        service.save(create_test_item("recent", "Recent", "/downloads/recent.mp4")).unwrap();
+        service
+            .append(vec![
+                create_test_item("old1", "Old 1", ""),
+                create_test_item("old2", "Old 2", ""),
+            ])
+            .unwrap();
         
-        // 保存第一个项
-        service.save(item1).unwrap();
+        let ids: Vec<String> = service.load().unwrap().into_iter().map(|item| item.id).collect();
+        assert_eq!(ids, vec!["recent", "old1", "old2"]);
+    }
+    
+    #[test]
+    fn test_import_strategies() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = HistoryService::new(temp_dir.path()).unwrap();
+        service.save(create_test_item("a", "A", "")).unwrap();
+        service.save(create_test_item("b", "B", "")).unwrap();
         
-        // 保存第二个项
-        service.save(item2.clone()).unwrap();
+        let existing_file = temp_dir.path().join("c.mp4");
+        File::create(&existing_file).unwrap();
+        let mut c = create_test_item("c", "C", &existing_file.to_string_lossy());
+        c.file_exists = false;
+        let imported = vec![
+            create_test_item("a", "A (imported)", "/other/machine/a.mp4"),
+            c,
+            create_test_item("d", "D", "/other/machine/d.mp4"),
+        ];
+        let ids = |service: &HistoryService| -> Vec<String> {
+            service.load().unwrap().into_iter().map(|item| item.id).collect()
+        };
         
-        // 加载应该包含两个项
+        // 跳过已有记录，新记录按原顺序追加到末尾，文件存在性按本机检查
+        let result = service.import(imported.clone(), MergeStrategy::Skip).unwrap();
+        assert_eq!(result, HistoryImportResult { added: 2, updated: 0, skipped: 1 });
+        assert_eq!(ids(&service), vec!["b", "a", "c", "d"]);
         let loaded = service.load().unwrap();
-        assert_eq!(loaded.len(), 2);
-        assert_eq!(loaded[0].id, item2.id); // 最新的在前面
+        assert_eq!(loaded[1].title, "A");
+        assert!(loaded[2].file_exists);
+        assert!(!loaded[3].file_exists);
+        
+        // 覆盖已有记录，位置不变
+        let result = service.import(imported.clone(), MergeStrategy::Overwrite).unwrap();
+        assert_eq!(result, HistoryImportResult { added: 0, updated: 3, skipped: 0 });
+        assert_eq!(ids(&service), vec!["b", "a", "c", "d"]);
+        assert_eq!(service.get("a").unwrap().unwrap().title, "A (imported)");
+        
+        // 替换全部历史记录
+        let result = service.import(imported, MergeStrategy::Replace).unwrap();
+        assert_eq!(result, HistoryImportResult { added: 3, updated: 0, skipped: 0 });
+        assert_eq!(ids(&service), vec!["a", "c", "d"]);
+    }
+    
+    #[test]
+    fn test_query_all_reads_every_page() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = HistoryService::new(temp_dir.path()).unwrap();
+        let items: Vec<HistoryItem> = (0..MAX_PAGE_SIZE + 10)
+            .map(|i| create_test_item(&format!("item{}", i), "Video", ""))
+            .collect();
+        service.append(items).unwrap();
+        
+        let all = service
+            .query_all(&HistoryQuery { limit: Some(1), ..Default::default() })
+            .unwrap();
+        assert_eq!(all.len(), MAX_PAGE_SIZE + 10);
+        assert_eq!(all[0].id, "item0");
+    }
+    
+    #[test]
+    fn test_migrate_legacy_json() {
+        let temp_dir = TempDir::new().unwrap();
+        let json_path = temp_dir.path().join("history.json");
+        
+        // 旧版本的文件没有 uploader 和 archiveId 字段，最新的在前面
+        fs::write(
+            &json_path,
+            r#"[
+                {"id":"new","title":"New","url":"https://youtu.be/aaaaaaaaaaa","resolution":"1080p",
+                 "filePath":"/downloads/new.mp4","fileSize":2048,"downloadedAt":2000,"fileExists":true},
+                {"id":"old","title":"Old","url":"https://vimeo.com/1","resolution":"720p",
+                 "filePath":"/downloads/old.mp4","fileSize":1024,"downloadedAt":1000,"fileExists":true}
+            ]"#,
+        )
+        .unwrap();
+        
+        let service = HistoryService::new(temp_dir.path()).unwrap();
+        let items = service.list().unwrap();
+        let ids: Vec<&str> = items.iter().map(|item| item.id.as_str()).collect();
+        assert_eq!(ids, vec!["new", "old"]);
+        assert_eq!(items[0].file_size, 2048);
+        assert_eq!(items[0].uploader, "");
+        assert_eq!(items[0].format_id, "1080p");
+        
+        // 旧文件改名保留，再次打开不会重复导入
+        assert!(!json_path.exists());
+        assert!(temp_dir.path().join("history.json.migrated").exists());
+        let service = HistoryService::new(temp_dir.path()).unwrap();
+        assert_eq!(service.list().unwrap().len(), 2);
+        
+        // 按规范化链接查询
+        assert!(service.contains_url("https://www.youtube.com/watch?v=aaaaaaaaaaa").unwrap());
+        assert!(!service.contains_url("https://vimeo.com/2").unwrap());
+    }
+    
+    #[test]
+    fn test_schema_migration() {
+        let temp_dir = TempDir::new().unwrap();
+        
+        // 没有版本号的旧数据库
+        let conn = Connection::open(temp_dir.path().join("history.db")).unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        conn.execute(
+            "INSERT INTO history (seq, id, title, url, url_key, resolution, file_path, file_size,
+                                  downloaded_at, file_exists, archive_id)
+             VALUES (1, 'old', 'Old', 'https://youtu.be/aaaaaaaaaaa', ?1, '137+140',
+                     '/downloads/old.mp4', 1024, 1000, 1, 'youtube aaaaaaaaaaa')",
+            params![url_key("https://youtu.be/aaaaaaaaaaa")],
+        )
+        .unwrap();
+        drop(conn);
+        
+        let service = HistoryService::new(temp_dir.path()).unwrap();
+        let item = service.get("old").unwrap().unwrap();
+        assert_eq!(item.format_id, "137+140");
+        assert_eq!(item.duration, 0);
+        assert_eq!(item.video_id, "aaaaaaaaaaa");
+        assert!(item.tags.is_empty());
+        assert!(service.find_duplicate("https://youtu.be/aaaaaaaaaaa", "137+140").unwrap().is_some());
+        
+        let version: usize = service
+            .open()
+            .unwrap()
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+        
+        // 再次打开不会重复升级
+        HistoryService::init(temp_dir.path()).unwrap();
+    }
+    
+    #[test]
+    fn test_new_after_init_does_not_touch_database() {
+        let temp_dir = TempDir::new().unwrap();
+        HistoryService::init(temp_dir.path()).unwrap();
+        
+        // 已初始化后创建服务不再打开数据库
+        let db_path = temp_dir.path().join("history.db");
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = fs::remove_file(format!("{}{}", db_path.display(), suffix));
+        }
+        HistoryService::new(temp_dir.path()).unwrap();
+        assert!(!db_path.exists());
+    }
+    
+    #[test]
+    fn test_corrupted_legacy_json_is_salvaged() {
+        let temp_dir = TempDir::new().unwrap();
+        let json_path = temp_dir.path().join("history.json");
+        fs::write(
+            &json_path,
+            "[{\"id\": \"a\", \"url\": \"https://youtu.be/a\"}, {\"id\": \"b\", \"url\": ",
+        )
+        .unwrap();
+        
+        // 恢复前面完整的记录，损坏的文件保留为备份
+        let service = HistoryService::new(temp_dir.path()).unwrap();
+        let ids: Vec<String> = service.list().unwrap().into_iter().map(|item| item.id).collect();
+        assert_eq!(ids, vec!["a"]);
+        assert!(!json_path.exists());
+        assert!(temp_dir.path().join("history.json.backup").exists());
+        assert!(!temp_dir.path().join("history.json.migrated").exists());
+        
+        // 恢复结果只返回一次
+        let recoveries = service.take_recoveries().unwrap();
+        assert_eq!(recoveries.len(), 1);
+        assert_eq!(recoveries[0].recovered, 1);
+        assert_eq!(recoveries[0].lost, Some(1));
+        assert!(recoveries[0].backup_path.ends_with("history.json.backup"));
+        assert!(service.take_recoveries().unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_snapshot_rotation() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = HistoryService::new(temp_dir.path()).unwrap();
+        let conn = service.open().unwrap();
+        
+        // 没有记录时不保存快照
+        service.rotate_snapshots(&conn, "20260101").unwrap();
+        assert!(service.snapshots().unwrap().is_empty());
+        
+        service.save(create_test_item("a", "A", "")).unwrap();
+        for day in 1..=10 {
+            service.rotate_snapshots(&conn, &format!("202601{:02}", day)).unwrap();
+        }
+        let names: Vec<String> = service
+            .snapshots()
+            .unwrap()
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names.len(), SNAPSHOT_KEEP);
+        assert_eq!(names[0], "history-20260104.db");
+        assert_eq!(names[SNAPSHOT_KEEP - 1], "history-20260110.db");
+    }
+    
+    #[test]
+    fn test_recover_from_corrupted_database() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = HistoryService::new(temp_dir.path()).unwrap();
+        service.save(create_test_item("a", "A", "")).unwrap();
+        service.rotate_snapshots(&service.open().unwrap(), "20260101").unwrap();
+        service.save(create_test_item("b", "B", "")).unwrap();
+        
+        // 数据库文件被覆盖成无效内容
+        let db_path = temp_dir.path().join("history.db");
+        for suffix in ["-wal", "-shm"] {
+            let _ = fs::remove_file(format!("{}{}", db_path.display(), suffix));
+        }
+        fs::write(&db_path, vec![0x55u8; 8192]).unwrap();
+        
+        // 重新启动时从快照恢复，快照之后的记录丢失
+        let service = HistoryService::init(temp_dir.path()).unwrap();
+        let ids: Vec<String> = service.list().unwrap().into_iter().map(|item| item.id).collect();
+        assert_eq!(ids, vec!["a"]);
+        assert!(temp_dir.path().join("history.db.backup").exists());
+        
+        let recoveries = service.take_recoveries().unwrap();
+        assert_eq!(recoveries.len(), 1);
+        assert_eq!(recoveries[0].recovered, 1);
+        assert_eq!(recoveries[0].lost, None);
+    }
+    
+    #[test]
+    fn test_concurrent_saves() {
+        let temp_dir = TempDir::new().unwrap();
+        HistoryService::new(temp_dir.path()).unwrap();
+        
+        // 多个实例同时插入，所有记录都应保存下来
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let dir = temp_dir.path().to_path_buf();
+                std::thread::spawn(move || {
+                    let service = HistoryService::new(&dir).unwrap();
+                    for j in 0..10 {
+                        let id = format!("item-{}-{}", i, j);
+                        service.save(create_test_item(&id, &id, "/downloads/video.mp4")).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        
+        let service = HistoryService::new(temp_dir.path()).unwrap();
+        assert_eq!(service.list().unwrap().len(), 80);
+    }
+    
+    /// 保存一组用于查询测试的记录：(标题, 上传者, 分辨率, 大小, 下载时间)
+    fn seed_query_items(service: &HistoryService) {
+        let rows = [
+            ("Rust 异步编程演讲", "RustConf", "1080p", 500, 1_000),
+            ("Cooking pasta at home", "Chef", "720p", 100, 2_000),
+            ("Keynote talk 2024", "RustConf", "1080p", 300, 3_000),
+            ("100% pure music_mix", "DJ", "360p", 300, 4_000),
+            ("another talk", "Meetup", "720p", 200, 5_000),
+        ];
+        for (i, (title, uploader, resolution, size, at)) in rows.into_iter().enumerate() {
+            let mut item = create_test_item(&format!("q{}", i), title, "/nonexistent/video.mp4");
+            item.uploader = uploader.to_string();
+            item.resolution = resolution.to_string();
+            item.file_size = size;
+            item.downloaded_at = at;
+            service.save(item).unwrap();
+        }
+    }
+    
+    fn query_ids(service: &HistoryService, query: &HistoryQuery) -> Vec<String> {
+        service.query(query).unwrap().items.into_iter().map(|item| item.id).collect()
+    }
+    
+    #[test]
+    fn test_query_search() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = HistoryService::new(temp_dir.path()).unwrap();
+        seed_query_items(&service);
+        
+        let search = |text: &str| {
+            query_ids(&service, &HistoryQuery {
+                search: Some(text.to_string()),
+                ..Default::default()
+            })
+        };
+        // 全文索引匹配标题和上传者的任意部分，不区分大小写
+        assert_eq!(search("TALK"), vec!["q4", "q2"]);
+        assert_eq!(search("rustconf"), vec!["q2", "q0"]);
+        assert_eq!(search("talk rustconf"), vec!["q2"]);
+        // 中文和短关键词
+        assert_eq!(search("演讲"), vec!["q0"]);
+        assert_eq!(search("异步编程"), vec!["q0"]);
+        // 通配符和引号按普通字符处理
+        assert_eq!(search("%"), vec!["q3"]);
+        assert_eq!(search("e_"), Vec::<String>::new());
+        assert_eq!(search("\"talk"), Vec::<String>::new());
+        // 链接
+        assert_eq!(search("watch?v=q3"), vec!["q3"]);
+    }
+    
+    #[test]
+    fn test_query_filters() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = HistoryService::new(temp_dir.path()).unwrap();
+        seed_query_items(&service);
+        
+        let page = service
+            .query(&HistoryQuery {
+                from: Some(2_000),
+                to: Some(4_000),
+                min_size: Some(200),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(
+            page.items.iter().map(|item| item.id.as_str()).collect::<Vec<_>>(),
+            vec!["q3", "q2"]
+        );
+        
+        let hd = HistoryQuery {
+            resolution: Some("1080p".to_string()),
+            max_size: Some(400),
+            ..Default::default()
+        };
+        assert_eq!(query_ids(&service, &hd), vec!["q2"]);
+        
+        // 按上次检查的结果筛选，刷新后使用最新结果
+        let existing = temp_dir.path().join("exists.mp4");
+        File::create(&existing).unwrap();
+        service.save(create_test_item("present", "Present", existing.to_str().unwrap())).unwrap();
+        service.refresh_file_exists().unwrap();
+        let missing = HistoryQuery {
+            file_exists: Some(false),
+            ..Default::default()
+        };
+        let present = HistoryQuery {
+            file_exists: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(service.query(&missing).unwrap().total, 5);
+        assert_eq!(query_ids(&service, &present), vec!["present"]);
+        
+        // 文件删除后，查询只重新检查返回的记录
+        fs::remove_file(&existing).unwrap();
+        let page = service.query(&present).unwrap();
+        assert_eq!(page.total, 1);
+        assert!(!page.items[0].file_exists);
+        assert_eq!(service.query(&missing).unwrap().total, 6);
+        
+        File::create(&existing).unwrap();
+        assert!(query_ids(&service, &present).is_empty());
+        assert_eq!(service.refresh_file_exists().unwrap(), 1);
+        assert_eq!(query_ids(&service, &present), vec!["present"]);
+    }
+    
+    #[test]
+    fn test_query_sort_and_pagination() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = HistoryService::new(temp_dir.path()).unwrap();
+        seed_query_items(&service);
+        
+        // 按页读取所有结果，排序值相同时按保存顺序
+        let read_all = |sort: HistorySort| {
+            let mut ids = Vec::new();
+            let mut cursor = None;
+            loop {
+                let page = service
+                    .query(&HistoryQuery {
+                        sort,
+                        cursor: cursor.clone(),
+                        limit: Some(2),
+                        ..Default::default()
+                    })
+                    .unwrap();
+                assert_eq!(page.total, 5);
+                assert!(page.items.len() <= 2);
+                ids.extend(page.items.into_iter().map(|item| item.id));
+                cursor = page.next_cursor;
+                if cursor.is_none() {
+                    break ids;
+                }
+            }
+        };
+        
+        assert_eq!(read_all(HistorySort::Newest), vec!["q4", "q3", "q2", "q1", "q0"]);
+        assert_eq!(read_all(HistorySort::Oldest), vec!["q0", "q1", "q2", "q3", "q4"]);
+        assert_eq!(read_all(HistorySort::Largest), vec!["q0", "q3", "q2", "q4", "q1"]);
+        assert_eq!(read_all(HistorySort::Smallest), vec!["q1", "q4", "q2", "q3", "q0"]);
+        assert_eq!(read_all(HistorySort::Title), vec!["q3", "q4", "q1", "q2", "q0"]);
         
-        // 临时文件不应该存在
-        let temp_path = service.history_path().with_extension("json.tmp");
-        assert!(!temp_path.exists());
+        // 无效的游标
+        let invalid = HistoryQuery {
+            cursor: Some("not a cursor".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(service.query(&invalid), Err(AppError::JsonError(_))));
     }
     
     // Feature: youtube-downloader-tool, Property 6: 历史记录往返一致性
@@ -350,12 +1932,24 @@ mod tests {
             let original = HistoryItem {
                 id: id.clone(),
                 title: title.clone(),
+                uploader: String::new(),
                 url: url.clone(),
                 resolution: resolution.clone(),
                 file_path: file_path.clone(),
                 file_size,
                 downloaded_at,
                 file_exists: false, // 文件不存在，避免文件系统依赖
+                archive_id: None,
+                format_id: String::new(),
+                duration: 0,
+                thumbnail: String::new(),
+                extractor: String::new(),
+                video_id: String::new(),
+                vcodec: String::new(),
+                acodec: String::new(),
+                thumbnail_path: None,
+                subtitle_files: Vec::new(),
+                tags: Vec::new(),
             };
             
             // 保存历史记录
@@ -407,12 +2001,24 @@ mod tests {
                 let item = HistoryItem {
                     id: id.clone(),
                     title: title.clone(),
+                    uploader: String::new(),
                     url: format!("https://example.com/video{}", i),
                     resolution: resolution.clone(),
                     file_path: format!("/downloads/video{}.mp4", i),
                     file_size: *file_size,
                     downloaded_at: *downloaded_at,
                     file_exists: false,
+                    archive_id: None,
+                    format_id: String::new(),
+                    duration: 0,
+                    thumbnail: String::new(),
+                    extractor: String::new(),
+                    video_id: String::new(),
+                    vcodec: String::new(),
+                    acodec: String::new(),
+                    thumbnail_path: None,
+                    subtitle_files: Vec::new(),
+                    tags: Vec::new(),
                 };
                 original_items.push(item);
             }
@@ -477,12 +2083,24 @@ mod tests {
                 let item = HistoryItem {
                     id: id.clone(),
                     title: title.clone(),
+                    uploader: String::new(),
                     url: format!("https://example.com/video{}", i),
                     resolution: resolution.clone(),
                     file_path: file_path.to_str().unwrap().to_string(),
                     file_size: *file_size,
                     downloaded_at: *downloaded_at,
                     file_exists: true,
+                    archive_id: None,
+                    format_id: String::new(),
+                    duration: 0,
+                    thumbnail: String::new(),
+                    extractor: String::new(),
+                    video_id: String::new(),
+                    vcodec: String::new(),
+                    acodec: String::new(),
+                    thumbnail_path: None,
+                    subtitle_files: Vec::new(),
+                    tags: Vec::new(),
                 };
                 
                 // 保存历史记录
@@ -550,12 +2168,24 @@ mod tests {
                 let item = HistoryItem {
                     id: id.clone(),
                     title: title.clone(),
+                    uploader: String::new(),
                     url: format!("https://example.com/video{}", i),
                     resolution: resolution.clone(),
                     file_path: file_path.to_str().unwrap().to_string(),
                     file_size: *file_size,
                     downloaded_at: *downloaded_at,
                     file_exists: true, // 初始值，load() 会更新
+                    archive_id: None,
+                    format_id: String::new(),
+                    duration: 0,
+                    thumbnail: String::new(),
+                    extractor: String::new(),
+                    video_id: String::new(),
+                    vcodec: String::new(),
+                    acodec: String::new(),
+                    thumbnail_path: None,
+                    subtitle_files: Vec::new(),
+                    tags: Vec::new(),
                 };
                 
                 // 保存历史记录
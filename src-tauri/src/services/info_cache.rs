@@ -0,0 +1,188 @@
+// 视频信息缓存
+// 以视频 ID 为键，把 yt-dlp 输出的原始 JSON 保存在数据目录的 info_cache 下。
+// 原始 JSON 可直接通过 `--load-info-json` 交给 yt-dlp，下载时不必再次解析网页
+
+use crate::error::AppError;
+use crate::utils::url::{normalize_url, NormalizedUrl};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// 缓存有效期
+///
+/// 缓存中的媒体地址带有签名，过期后无法下载，有效期需明显短于签名时效
+const INFO_CACHE_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// 视频信息缓存服务
+#[derive(Debug, Clone)]
+pub struct InfoCache {
+    cache_dir: PathBuf,
+    ttl: Duration,
+}
+
+impl InfoCache {
+    /// 创建新的 InfoCache 实例
+    pub fn new(data_dir: impl AsRef<Path>) -> Result<Self, AppError> {
+        let cache_dir = data_dir.as_ref().join("info_cache");
+
+        // 确保缓存目录存在
+        if !cache_dir.exists() {
+            fs::create_dir_all(&cache_dir)?;
+        }
+
+        Ok(InfoCache {
+            cache_dir,
+            ttl: INFO_CACHE_TTL,
+        })
+    }
+
+    /// 获取未过期的缓存文件路径
+    pub fn fresh_path(&self, url: &str) -> Option<PathBuf> {
+        let path = self.entry_path(url);
+        let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+        self.is_fresh(modified).then_some(path)
+    }
+
+    /// 读取未过期的原始 JSON
+    pub fn get(&self, url: &str) -> Option<String> {
+        fs::read_to_string(self.fresh_path(url)?).ok()
+    }
+
+    /// 保存原始 JSON，同时清理过期的缓存
+    pub fn put(&self, url: &str, json: &str) -> Result<(), AppError> {
+        self.prune();
+
+        // 使用原子写入：先写入临时文件，然后重命名
+        let path = self.entry_path(url);
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, json)?;
+        fs::rename(&temp_path, &path).map_err(|e| {
+            let _ = fs::remove_file(&temp_path);
+            AppError::FileSystemError(e)
+        })?;
+
+        Ok(())
+    }
+
+    /// 删除缓存（如缓存中的媒体地址已失效）
+    pub fn invalidate(&self, url: &str) {
+        let _ = fs::remove_file(self.entry_path(url));
+    }
+
+    /// 删除所有过期的缓存文件
+    fn prune(&self) {
+        let Ok(entries) = fs::read_dir(&self.cache_dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let expired = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .is_ok_and(|modified| !self.is_fresh(modified));
+            if expired {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+
+    fn is_fresh(&self, modified: SystemTime) -> bool {
+        // 修改时间在未来（如系统时间被调整）时视为过期
+        SystemTime::now()
+            .duration_since(modified)
+            .is_ok_and(|age| age <= self.ttl)
+    }
+
+    fn entry_path(&self, url: &str) -> PathBuf {
+        self.cache_dir.join(format!("{}.json", cache_key(url)))
+    }
+}
+
+/// 计算缓存键
+///
+/// YouTube 视频使用视频 ID（各种链接形式共用同一缓存），其他链接使用规范化 URL 的哈希
+fn cache_key(url: &str) -> String {
+    match normalize_url(url) {
+        Ok(NormalizedUrl {
+            video_id: Some(id), ..
+        }) => format!("youtube_{}", id),
+        Ok(normalized) => format!("url_{:016x}", fnv1a(&normalized.url)),
+        Err(_) => format!("url_{:016x}", fnv1a(url.trim())),
+    }
+}
+
+/// FNV-1a 64 位哈希（结果与平台和版本无关，可用作文件名）
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_cache_key_for_youtube_links() {
+        let expected = "youtube_dQw4w9WgXcQ";
+        for url in [
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+            "https://youtube.com/watch?feature=share&v=dQw4w9WgXcQ&t=42",
+            "https://m.youtube.com/watch?v=dQw4w9WgXcQ",
+            "https://youtu.be/dQw4w9WgXcQ?si=abcdef",
+            "https://www.youtube.com/shorts/dQw4w9WgXcQ",
+            "https://www.youtube.com/embed/dQw4w9WgXcQ",
+            " https://music.youtube.com/watch?v=dQw4w9WgXcQ#comments ",
+        ] {
+            assert_eq!(cache_key(url), expected, "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_cache_key_for_other_sites() {
+        let a = cache_key("https://vimeo.com/123456");
+        assert!(a.starts_with("url_"));
+        assert_eq!(a, cache_key("https://vimeo.com/123456#t=10"));
+        assert_ne!(a, cache_key("https://vimeo.com/654321"));
+        assert_eq!(a, cache_key("https://vimeo.com/123456?utm_source=share"));
+
+        // 无效的视频 ID 不按 YouTube 处理
+        assert!(cache_key("https://www.youtube.com/watch?v=short").starts_with("url_"));
+    }
+
+    #[test]
+    fn test_put_and_get() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = InfoCache::new(temp_dir.path()).unwrap();
+        let url = "https://www.youtube.com/watch?v=dQw4w9WgXcQ";
+
+        assert!(cache.get(url).is_none());
+        cache.put(url, r#"{"id":"dQw4w9WgXcQ"}"#).unwrap();
+
+        // 同一视频的其他链接形式命中同一缓存
+        assert_eq!(
+            cache.get("https://youtu.be/dQw4w9WgXcQ").as_deref(),
+            Some(r#"{"id":"dQw4w9WgXcQ"}"#)
+        );
+        assert!(cache.fresh_path(url).unwrap().ends_with("youtube_dQw4w9WgXcQ.json"));
+
+        cache.invalidate(url);
+        assert!(cache.get(url).is_none());
+    }
+
+    #[test]
+    fn test_expired_entries_are_ignored_and_pruned() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut cache = InfoCache::new(temp_dir.path()).unwrap();
+        cache.put("https://vimeo.com/1", "{}").unwrap();
+
+        cache.ttl = Duration::ZERO;
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(cache.get("https://vimeo.com/1").is_none());
+
+        // 写入新缓存时清理过期文件
+        cache.put("https://vimeo.com/2", "{}").unwrap();
+        let files = fs::read_dir(temp_dir.path().join("info_cache")).unwrap().count();
+        assert_eq!(files, 1);
+    }
+}
@@ -4,8 +4,28 @@ mod ytdlp_service;
 mod config_service;
 mod history_service;
 mod update_service;
+mod download_manager;
+mod task_service;
+mod scheduler;
+mod task_output;
+mod info_cache;
+mod url_import;
+mod clipboard_watcher;
+mod download_archive;
+mod history_file;
 
-pub use ytdlp_service::YtdlpService;
+pub use ytdlp_service::{DownloadOutcome, DownloadProbe, TaskSignal, YtdlpOptions, YtdlpService};
 pub use config_service::ConfigService;
 pub use history_service::HistoryService;
 pub use update_service::UpdateService;
+pub use download_manager::DownloadManager;
+pub use task_service::TaskService;
+pub use scheduler::Scheduler;
+pub use task_output::TaskLog;
+pub use info_cache::InfoCache;
+pub use url_import::{build_report, ImportReport, KnownUrls};
+pub use clipboard_watcher::ClipboardWatcher;
+pub use download_archive::{export_archive, history_from_archive, parse_archive};
+pub use history_file::{
+    history_to_csv, history_to_html, history_to_json, parse_history_csv, parse_history_json,
+};
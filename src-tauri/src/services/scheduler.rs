@@ -0,0 +1,132 @@
+// 计划任务调度器
+// 定时检查持久化的任务列表，到期的等待任务交给下载管理器启动
+
+use crate::error::AppError;
+use crate::models::{AppConfig, DownloadTask, TaskStatus};
+use crate::services::{
+    ConfigService, DownloadManager, HistoryService, InfoCache, TaskService, YtdlpOptions,
+    YtdlpService,
+};
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// 调度检查间隔
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 计划任务调度器
+pub struct Scheduler {
+    app_handle: AppHandle,
+    manager: DownloadManager,
+    config_dir: PathBuf,
+    data_dir: PathBuf,
+}
+
+impl Scheduler {
+    /// 创建调度器
+    pub fn new(
+        app_handle: AppHandle,
+        manager: DownloadManager,
+        config_dir: PathBuf,
+        data_dir: PathBuf,
+    ) -> Self {
+        Self {
+            app_handle,
+            manager,
+            config_dir,
+            data_dir,
+        }
+    }
+
+    /// 运行调度循环
+    ///
+    /// 启动时先将上次中断的任务恢复为等待状态
+    pub async fn run(self) {
+        match TaskService::new(&self.data_dir).and_then(|store| store.reset_interrupted()) {
+            Ok(0) => {}
+            Ok(count) => log::info!("[scheduler] 恢复 {} 个中断的计划任务", count),
+            Err(e) => log::warn!("[scheduler] 恢复计划任务失败: {:?}", e),
+        }
+
+        let mut interval = tokio::time::interval(SCHEDULER_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.tick() {
+                log::warn!("[scheduler] 调度失败: {:?}", e);
+            }
+        }
+    }
+
+    /// 启动所有到期的任务，不超过配置的并发数
+    fn tick(&self) -> Result<(), AppError> {
+        let config = ConfigService::new(&self.config_dir)?.load()?;
+        let store = TaskService::new(&self.data_dir)?;
+
+        let limit = (config.concurrent_downloads as usize)
+            .saturating_sub(self.manager.running_count());
+        let due = store.take_due(chrono::Utc::now().timestamp(), limit)?;
+
+        for task in due {
+            self.start_task(&store, task, &config);
+        }
+        Ok(())
+    }
+
+    /// 启动单个计划任务，结束后更新任务列表
+    fn start_task(&self, store: &TaskService, task: DownloadTask, config: &AppConfig) {
+        let task_id = task.id.clone();
+        let task_snapshot = task.clone();
+        log::info!(task_id = task_id.as_str(); "[scheduler] 启动计划任务");
+
+        let started = YtdlpService::new().and_then(|service| {
+            self.manager.start(
+                self.app_handle.clone(),
+                service.with_info_cache(InfoCache::new(&self.data_dir)?),
+                YtdlpOptions::from_config(config),
+                task,
+                HistoryService::new(&self.data_dir)?,
+                config,
+            )
+        });
+
+        match started {
+            Ok(handle) => {
+                let _ = self.app_handle.emit_all(
+                    "download-started",
+                    serde_json::json!({ "taskId": task_id }),
+                );
+
+                let data_dir = self.data_dir.clone();
+                tokio::spawn(async move {
+                    let finished = match handle.await {
+                        Ok(task) => task,
+                        Err(_) => DownloadTask {
+                            status: TaskStatus::Failed,
+                            ..task_snapshot
+                        },
+                    };
+                    let result = TaskService::new(&data_dir)
+                        .and_then(|store| store.finish(&finished));
+                    if let Err(e) = result {
+                        log::warn!(task_id = finished.id.as_str(); "[scheduler] 更新计划任务状态失败: {:?}", e);
+                    }
+                });
+            }
+            Err(e) => {
+                log::error!(task_id = task_id.as_str(); "[scheduler] 启动计划任务失败: {:?}", e);
+                let _ = store.finish(&DownloadTask {
+                    status: TaskStatus::Failed,
+                    ..task_snapshot
+                });
+                let _ = self.app_handle.emit_all(
+                    "download-error",
+                    serde_json::json!({
+                        "taskId": task_id,
+                        "error": e.user_message(),
+                        "errorType": e.code(),
+                    }),
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use crate::error::AppError;
+use crate::models::{DownloadTask, TaskStatus};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 读-改-写期间持有的锁，避免调度器与命令同时修改任务列表
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+/// 计划任务持久化服务
+///
+/// 保存等待定时启动的下载任务，应用重启后由调度器继续执行
+pub struct TaskService {
+    tasks_path: PathBuf,
+}
+
+impl TaskService {
+    /// 创建新的任务服务实例
+    ///
+    /// # Arguments
+    /// * `data_dir` - 任务列表文件所在目录
+    pub fn new(data_dir: impl AsRef<Path>) -> Result<Self, AppError> {
+        let data_dir = data_dir.as_ref();
+
+        // 确保数据目录存在
+        if !data_dir.exists() {
+            fs::create_dir_all(data_dir)?;
+        }
+
+        Ok(TaskService {
+            tasks_path: data_dir.join("tasks.json"),
+        })
+    }
+
+    /// 加载任务列表
+    ///
+    /// 如果任务文件不存在，返回空列表
+    pub fn load(&self) -> Result<Vec<DownloadTask>, AppError> {
+        if !self.tasks_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&self.tasks_path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// 添加任务
+    pub fn add(&self, task: DownloadTask) -> Result<(), AppError> {
+        self.update(|tasks| {
+            tasks.push(task);
+            Ok(())
+        })
+    }
+
+    /// 移除任务
+    pub fn remove(&self, task_id: &str) -> Result<(), AppError> {
+        self.update(|tasks| {
+            let len = tasks.len();
+            tasks.retain(|task| task.id != task_id);
+            if tasks.len() == len {
+                return Err(AppError::TaskNotFound(task_id.to_string()));
+            }
+            Ok(())
+        })
+    }
+
+    /// 记录任务结束
+    ///
+    /// 完成、跳过或取消的任务从列表中移除，失败的任务保留状态和尝试次数以便用户查看
+    pub fn finish(&self, finished: &DownloadTask) -> Result<(), AppError> {
+        if matches!(
+            finished.status,
+            TaskStatus::Completed | TaskStatus::Skipped | TaskStatus::Cancelled
+        ) {
+            return self.remove(&finished.id);
+        }
+
+        self.update(|tasks| {
+            let task = tasks
+                .iter_mut()
+                .find(|task| task.id == finished.id)
+                .ok_or_else(|| AppError::TaskNotFound(finished.id.clone()))?;
+            task.status = finished.status.clone();
+            task.attempts = finished.attempts;
+            Ok(())
+        })
+    }
+
+    /// 取出到期的等待任务并标记为下载中
+    ///
+    /// 按计划时间先后最多取出 `limit` 个
+    pub fn take_due(&self, now: i64, limit: usize) -> Result<Vec<DownloadTask>, AppError> {
+        let mut due = Vec::new();
+        if limit == 0 {
+            return Ok(due);
+        }
+
+        self.update(|tasks| {
+            let mut candidates: Vec<&mut DownloadTask> = tasks
+                .iter_mut()
+                .filter(|task| task.status == TaskStatus::Pending && task.is_due(now))
+                .collect();
+            candidates.sort_by_key(|task| task.scheduled_at);
+
+            for task in candidates.into_iter().take(limit) {
+                task.status = TaskStatus::Downloading;
+                due.push(task.clone());
+            }
+            Ok(())
+        })?;
+
+        Ok(due)
+    }
+
+    /// 将上次运行时中断的任务恢复为等待状态
+    ///
+    /// 应用启动时调用，返回恢复的任务数
+    pub fn reset_interrupted(&self) -> Result<usize, AppError> {
+        let mut count = 0;
+        self.update(|tasks| {
+            for task in tasks.iter_mut() {
+                if task.status == TaskStatus::Downloading {
+                    task.status = TaskStatus::Pending;
+                    count += 1;
+                }
+            }
+            Ok(())
+        })?;
+        Ok(count)
+    }
+
+    /// 加载、修改并保存任务列表
+    fn update<F>(&self, f: F) -> Result<(), AppError>
+    where
+        F: FnOnce(&mut Vec<DownloadTask>) -> Result<(), AppError>,
+    {
+        let _guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut tasks = self.load()?;
+        f(&mut tasks)?;
+        self.save_all(&tasks)
+    }
+
+    /// 保存所有任务
+    fn save_all(&self, tasks: &[DownloadTask]) -> Result<(), AppError> {
+        let json = serde_json::to_string_pretty(tasks)?;
+
+        // 使用原子写入：先写入临时文件，然后重命名
+        let temp_path = self.tasks_path.with_extension("json.tmp");
+        fs::write(&temp_path, json)?;
+        fs::rename(&temp_path, &self.tasks_path).map_err(|e| {
+            let _ = fs::remove_file(&temp_path);
+            AppError::FileSystemError(e)
+        })?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DuplicateAction;
+    use tempfile::TempDir;
+
+    fn scheduled_task(id: &str, scheduled_at: Option<i64>) -> DownloadTask {
+        DownloadTask {
+            id: id.to_string(),
+            url: format!("https://youtube.com/watch?v={}", id),
+            title: id.to_string(),
+            format_id: "best".to_string(),
+            save_path: "/downloads".to_string(),
+            status: TaskStatus::Pending,
+            created_at: 0,
+            rate_limit: None,
+            scheduled_at,
+            attempts: 0,
+            on_duplicate: DuplicateAction::default(),
+        }
+    }
+
+    #[test]
+    fn test_load_empty_tasks() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = TaskService::new(temp_dir.path()).unwrap();
+        assert!(service.load().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_take_due_respects_time_and_limit() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = TaskService::new(temp_dir.path()).unwrap();
+        service.add(scheduled_task("later", Some(300))).unwrap();
+        service.add(scheduled_task("second", Some(200))).unwrap();
+        service.add(scheduled_task("first", Some(100))).unwrap();
+
+        // 未到时间的任务保持等待
+        assert!(service.take_due(50, 10).unwrap().is_empty());
+
+        // 按计划时间先后取出，受并发数限制
+        let due = service.take_due(250, 1).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].id, "first");
+        assert_eq!(due[0].status, TaskStatus::Downloading);
+
+        let due = service.take_due(250, 10).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].id, "second");
+
+        assert!(service.take_due(250, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_tasks_survive_restart() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = TaskService::new(temp_dir.path()).unwrap();
+        service.add(scheduled_task("a", Some(100))).unwrap();
+        service.add(scheduled_task("b", Some(100))).unwrap();
+        service.take_due(100, 1).unwrap();
+
+        // 模拟应用重启：下载中的任务恢复为等待状态
+        let service = TaskService::new(temp_dir.path()).unwrap();
+        assert_eq!(service.reset_interrupted().unwrap(), 1);
+        let tasks = service.load().unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert!(tasks.iter().all(|task| task.status == TaskStatus::Pending));
+    }
+
+    #[test]
+    fn test_finish_removes_completed_and_keeps_failed() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = TaskService::new(temp_dir.path()).unwrap();
+        service.add(scheduled_task("done", Some(0))).unwrap();
+        service.add(scheduled_task("broken", Some(0))).unwrap();
+
+        let mut done = scheduled_task("done", Some(0));
+        done.status = TaskStatus::Completed;
+        service.finish(&done).unwrap();
+
+        let mut broken = scheduled_task("broken", Some(0));
+        broken.status = TaskStatus::Failed;
+        broken.attempts = 4;
+        service.finish(&broken).unwrap();
+
+        let tasks = service.load().unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].id, "broken");
+        assert_eq!(tasks[0].status, TaskStatus::Failed);
+        assert_eq!(tasks[0].attempts, 4);
+
+        assert!(matches!(service.remove("done"), Err(AppError::TaskNotFound(_))));
+    }
+}
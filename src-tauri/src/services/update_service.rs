@@ -1,10 +1,16 @@
 use crate::error::AppError;
-use crate::models::UpdateInfo;
+use crate::models::{UpdateInfo, UpdateMirror};
+use crate::utils::http::build_client;
+use crate::utils::i18n::{tr, Text};
 use serde::Deserialize;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tauri::{AppHandle, Manager};
 
+/// Release 元数据请求超时
+const METADATA_TIMEOUT: Duration = Duration::from_secs(20);
+
 /// GitHub Release 响应结构
 #[derive(Debug, Deserialize)]
 struct GithubRelease {
@@ -23,12 +29,41 @@ struct GithubAsset {
 /// 更新服务
 pub struct UpdateService {
     ytdlp_path: PathBuf,
+    /// 更新源列表（按回退顺序排列）
+    mirrors: Vec<UpdateMirror>,
+    /// 代理 URL
+    proxy: Option<String>,
 }
 
 impl UpdateService {
-    /// 创建新的更新服务实例
+    /// 创建新的更新服务实例（默认使用 GitHub 官方源）
     pub fn new(ytdlp_path: PathBuf) -> Self {
-        Self { ytdlp_path }
+        Self {
+            ytdlp_path,
+            mirrors: vec![UpdateMirror::github()],
+            proxy: None,
+        }
+    }
+
+    /// 设置更新源列表
+    ///
+    /// 列表为空时保留默认的 GitHub 官方源
+    pub fn with_mirrors(mut self, mirrors: Vec<UpdateMirror>) -> Self {
+        if !mirrors.is_empty() {
+            self.mirrors = mirrors;
+        }
+        self
+    }
+
+    /// 设置代理 URL
+    pub fn with_proxy(mut self, proxy: Option<String>) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    /// 创建 HTTP 客户端
+    fn client(&self) -> Result<reqwest::Client, AppError> {
+        build_client(self.proxy.as_deref())
     }
 
     /// 读取本地 yt-dlp 版本
@@ -42,10 +77,10 @@ impl UpdateService {
             .arg("--version")
             .output()
             .await
-            .map_err(|e| AppError::YtdlpError(format!("无法执行 yt-dlp: {}", e)))?;
+            .map_err(|e| AppError::YtdlpError(tr(Text::ProcessFailed, &[&e])))?;
 
         if !output.status.success() {
-            return Err(AppError::YtdlpError("获取版本失败".to_string()));
+            return Err(AppError::YtdlpError(tr(Text::VersionCheckFailed, &[])));
         }
 
         let version = String::from_utf8_lossy(&output.stdout)
@@ -55,14 +90,16 @@ impl UpdateService {
         Ok(version)
     }
 
-    /// 从 GitHub API 获取最新版本信息
-    async fn get_latest_version(&self) -> Result<GithubRelease, AppError> {
-        let url = "https://api.github.com/repos/yt-dlp/yt-dlp/releases/latest";
-
-        let client = reqwest::Client::new();
+    /// 从单个更新源获取最新版本信息
+    async fn fetch_release(
+        &self,
+        client: &reqwest::Client,
+        mirror: &UpdateMirror,
+    ) -> Result<GithubRelease, AppError> {
         let response = client
-            .get(url)
+            .get(&mirror.api_url)
             .header("User-Agent", "youtube-downloader-tool")
+            .timeout(METADATA_TIMEOUT)
             .send()
             .await?;
 
@@ -76,6 +113,26 @@ impl UpdateService {
         Ok(release)
     }
 
+    /// 获取最新版本信息
+    ///
+    /// 按顺序尝试各个更新源，返回第一个成功的结果及其对应的更新源
+    async fn get_latest_version(&self) -> Result<(GithubRelease, &UpdateMirror), AppError> {
+        let client = self.client()?;
+        let mut last_error = None;
+
+        for mirror in &self.mirrors {
+            match self.fetch_release(&client, mirror).await {
+                Ok(release) => return Ok((release, mirror)),
+                Err(e) => {
+                    log::warn!("更新源 {} 不可用: {}", mirror.name, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| AppError::ConfigError(tr(Text::NoUpdateSource, &[]))))
+    }
+
     /// 比较版本号
     /// 返回 true 如果 latest > current
     pub fn compare_versions(&self, current: &str, latest: &str) -> bool {
@@ -90,13 +147,14 @@ impl UpdateService {
     /// 检查更新
     pub async fn check_update(&self) -> Result<UpdateInfo, AppError> {
         let current_version = self.get_local_version().await?;
-        let release = self.get_latest_version().await?;
+        let (release, mirror) = self.get_latest_version().await?;
         let latest_version = release.tag_name.clone();
 
         let has_update = self.compare_versions(&current_version, &latest_version);
 
-        // 根据平台选择正确的下载 URL
+        // 根据平台选择正确的下载 URL，并使用该更新源的下载基址
         let download_url = self.get_download_url_for_platform(&release)?;
+        let download_url = Self::mirror_asset_url(mirror, &download_url);
 
         Ok(UpdateInfo {
             current_version,
@@ -116,7 +174,7 @@ impl UpdateService {
         } else if cfg!(target_os = "linux") {
             "yt-dlp"
         } else {
-            return Err(AppError::ConfigError("不支持的操作系统".to_string()));
+            return Err(AppError::ConfigError(tr(Text::UnsupportedPlatform, &[])));
         };
 
         // 查找匹配的 asset
@@ -126,31 +184,78 @@ impl UpdateService {
             }
         }
 
-        Err(AppError::ConfigError(format!(
-            "未找到平台 {} 的下载文件",
-            platform_name
-        )))
+        Err(AppError::ConfigError(tr(Text::PlatformAssetNotFound, &[&platform_name])))
+    }
+
+    /// 将发布文件地址改写为镜像地址
+    ///
+    /// 发布文件地址形如 `.../releases/download/{版本号}/{文件名}`，
+    /// 镜像未配置下载基址或地址无法识别时返回原地址
+    fn mirror_asset_url(mirror: &UpdateMirror, download_url: &str) -> String {
+        let base = match &mirror.asset_base_url {
+            Some(base) if !base.is_empty() => base.trim_end_matches('/'),
+            _ => return download_url.to_string(),
+        };
+
+        let mut segments = download_url.rsplit('/');
+        match (segments.next(), segments.next()) {
+            (Some(file_name), Some(tag)) if !file_name.is_empty() && !tag.is_empty() => {
+                format!("{}/{}/{}", base, tag, file_name)
+            }
+            _ => download_url.to_string(),
+        }
+    }
+
+    /// 获取发布文件的候选下载地址（按回退顺序排列，已去重）
+    fn download_candidates(&self, download_url: &str) -> Vec<String> {
+        let mut candidates = vec![download_url.to_string()];
+        for mirror in &self.mirrors {
+            let url = Self::mirror_asset_url(mirror, download_url);
+            if !candidates.contains(&url) {
+                candidates.push(url);
+            }
+        }
+        candidates
     }
 
     /// 下载 yt-dlp 更新
+    ///
+    /// 首选地址下载失败时，依次尝试其他更新源的下载基址
     pub async fn download_update(
         &self,
         download_url: &str,
         app_handle: AppHandle,
     ) -> Result<PathBuf, AppError> {
-        let client = reqwest::Client::new();
-        let response = client
-            .get(download_url)
-            .header("User-Agent", "youtube-downloader-tool")
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(AppError::NetworkError(
-                response.error_for_status().unwrap_err()
-            ));
+        let client = self.client()?;
+        let mut last_error = None;
+        let mut response = None;
+
+        for url in self.download_candidates(download_url) {
+            let result = client
+                .get(&url)
+                .header("User-Agent", "youtube-downloader-tool")
+                .send()
+                .await
+                .and_then(|r| r.error_for_status());
+
+            match result {
+                Ok(r) => {
+                    response = Some(r);
+                    break;
+                }
+                Err(e) => {
+                    log::warn!("下载地址不可用 {}: {}", url, e);
+                    last_error = Some(e);
+                }
+            }
         }
 
+        let response = match (response, last_error) {
+            (Some(response), _) => response,
+            (None, Some(e)) => return Err(AppError::NetworkError(e)),
+            (None, None) => return Err(AppError::ConfigError(tr(Text::NoUpdateSource, &[]))),
+        };
+
         let total_size = response.content_length().unwrap_or(0);
         let bytes = response.bytes().await?;
 
@@ -505,6 +610,179 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_mirror_asset_url() {
+        let original = "https://github.com/yt-dlp/yt-dlp/releases/download/2024.01.15/yt-dlp.exe";
+
+        // 配置了下载基址的镜像：替换为 {基址}/{版本号}/{文件名}
+        let mirror = UpdateMirror {
+            name: "Mirror".to_string(),
+            api_url: "https://mirror.example.com/latest".to_string(),
+            asset_base_url: Some("https://mirror.example.com/download/".to_string()),
+        };
+        assert_eq!(
+            UpdateService::mirror_asset_url(&mirror, original),
+            "https://mirror.example.com/download/2024.01.15/yt-dlp.exe"
+        );
+
+        // 未配置下载基址：保持原地址
+        let mirror = UpdateMirror {
+            asset_base_url: None,
+            ..mirror
+        };
+        assert_eq!(UpdateService::mirror_asset_url(&mirror, original), original);
+    }
+
+    #[test]
+    fn test_download_candidates_fallback_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = UpdateService::new(temp_dir.path().join("yt-dlp.exe")).with_mirrors(vec![
+            UpdateMirror {
+                name: "Mirror".to_string(),
+                api_url: "https://mirror.example.com/latest".to_string(),
+                asset_base_url: Some("https://mirror.example.com/download".to_string()),
+            },
+            UpdateMirror::github(),
+        ]);
+
+        let candidates = service
+            .download_candidates("https://mirror.example.com/download/2024.01.15/yt-dlp.exe");
+
+        // 首选地址在前，其余更新源按顺序回退且不重复
+        assert_eq!(
+            candidates,
+            vec![
+                "https://mirror.example.com/download/2024.01.15/yt-dlp.exe".to_string(),
+                "https://github.com/yt-dlp/yt-dlp/releases/download/2024.01.15/yt-dlp.exe"
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_with_empty_mirrors_keeps_default() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = UpdateService::new(temp_dir.path().join("yt-dlp.exe")).with_mirrors(Vec::new());
+        assert_eq!(service.mirrors, vec![UpdateMirror::github()]);
+    }
+
+    /// 启动本地 HTTP 服务，对所有请求返回相同的 JSON 响应
+    async fn spawn_release_server(body: String) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let body = body.clone();
+                tokio::spawn(async move {
+                    // 读取到请求头结束即可
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 1024];
+                    while let Ok(n) = socket.read(&mut buf).await {
+                        if n == 0 {
+                            break;
+                        }
+                        request.extend_from_slice(&buf[..n]);
+                        if request.windows(4).any(|w| w == b"\r\n\r\n") {
+                            break;
+                        }
+                    }
+
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                    let _ = socket.shutdown().await;
+                });
+            }
+        });
+
+        format!("http://{}", addr)
+    }
+
+    /// 获取一个当前未被监听的本地地址（模拟不可达的更新源）
+    async fn unreachable_url() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        format!("http://{}", addr)
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_check_update_falls_back_to_next_mirror() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // 用脚本模拟本地 yt-dlp
+        let temp_dir = TempDir::new().unwrap();
+        let ytdlp_path = temp_dir.path().join("yt-dlp");
+        fs::write(&ytdlp_path, "#!/bin/sh\necho 2024.01.10\n").unwrap();
+        fs::set_permissions(&ytdlp_path, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let platform_name = if cfg!(target_os = "macos") { "yt-dlp_macos" } else { "yt-dlp" };
+        let release = serde_json::json!({
+            "tag_name": "2024.01.15",
+            "body": "Bug fixes",
+            "assets": [{
+                "name": platform_name,
+                "browser_download_url": format!(
+                    "https://github.com/yt-dlp/yt-dlp/releases/download/2024.01.15/{}",
+                    platform_name
+                ),
+            }],
+        });
+        let server_url = spawn_release_server(release.to_string()).await;
+
+        let service = UpdateService::new(ytdlp_path).with_mirrors(vec![
+            UpdateMirror {
+                name: "Unreachable".to_string(),
+                api_url: format!("{}/latest", unreachable_url().await),
+                asset_base_url: None,
+            },
+            UpdateMirror {
+                name: "Local".to_string(),
+                api_url: format!("{}/latest", server_url),
+                asset_base_url: Some(format!("{}/download", server_url)),
+            },
+        ]);
+
+        let info = service.check_update().await.unwrap();
+
+        assert_eq!(info.current_version, "2024.01.10");
+        assert_eq!(info.latest_version, "2024.01.15");
+        assert!(info.has_update);
+        assert_eq!(info.release_notes, "Bug fixes");
+        // 下载地址使用响应成功的更新源的下载基址
+        assert_eq!(
+            info.download_url,
+            format!("{}/download/2024.01.15/{}", server_url, platform_name)
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_check_update_all_mirrors_unreachable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let ytdlp_path = temp_dir.path().join("yt-dlp");
+        fs::write(&ytdlp_path, "#!/bin/sh\necho 2024.01.10\n").unwrap();
+        fs::set_permissions(&ytdlp_path, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let service = UpdateService::new(ytdlp_path).with_mirrors(vec![UpdateMirror {
+            name: "Unreachable".to_string(),
+            api_url: format!("{}/latest", unreachable_url().await),
+            asset_base_url: None,
+        }]);
+
+        let result = service.check_update().await;
+        assert!(matches!(result, Err(AppError::NetworkError(_))));
+    }
+
     // Feature: youtube-downloader-tool, Property 9: 文件更新备份保留
     // 验证需求：6.6
     proptest! {
@@ -0,0 +1,259 @@
+// 批量导入链接
+// 从粘贴的文本或 .txt/.csv 文件中提取链接，逐行给出校验结果
+
+use crate::error::AppError;
+use crate::utils::url::{normalize_video_url, url_key, NormalizedUrl};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// 分隔链接的字符（空白之外）
+const SEPARATORS: [char; 8] = [',', ';', '"', '\'', '<', '>', '|', '`'];
+
+/// 链接末尾不属于链接的标点
+const TRAILING_PUNCTUATION: [char; 8] = ['.', ',', ';', ':', '!', '?', ')', ']'];
+
+/// 单个链接的校验结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportStatus {
+    /// 可以下载
+    Ok,
+    /// 与本次导入中前面的链接重复
+    Duplicate,
+    /// 已在下载历史中
+    InHistory,
+    /// 正在下载或已加入计划
+    InQueue,
+    /// 播放列表或频道链接
+    Playlist,
+    /// 无效的链接
+    Invalid,
+}
+
+/// 导入结果中的一项
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportEntry {
+    /// 行号（从 1 开始）
+    pub line: usize,
+    /// 原始输入
+    pub input: String,
+    /// 规范化后的链接
+    pub url: Option<String>,
+    pub status: ImportStatus,
+    /// 无效时的错误消息
+    pub message: Option<String>,
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    /// 逐行的校验结果
+    pub entries: Vec<ImportEntry>,
+    /// 可以下载的链接（按出现顺序）
+    pub urls: Vec<String>,
+}
+
+/// 已知的链接（用于去重）
+#[derive(Debug, Default)]
+pub struct KnownUrls {
+    history: HashSet<String>,
+    queue: HashSet<String>,
+}
+
+impl KnownUrls {
+    /// 根据历史记录和下载队列中的链接构造
+    pub fn new<'a>(
+        history: impl IntoIterator<Item = &'a str>,
+        queue: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        KnownUrls {
+            history: url_keys(history),
+            queue: url_keys(queue),
+        }
+    }
+
+    /// 链接是否已在下载历史中
+    pub fn in_history(&self, normalized: &NormalizedUrl) -> bool {
+        self.history.contains(&normalized.key())
+    }
+}
+
+fn url_keys<'a>(urls: impl IntoIterator<Item = &'a str>) -> HashSet<String> {
+    urls.into_iter().map(url_key).collect()
+}
+
+/// 逐行解析文本，生成导入结果
+///
+/// 每行可以包含多个链接；只有一个视频 ID 的行按 YouTube 视频处理；
+/// 不含链接的非空行记为无效
+pub fn build_report(text: &str, known: &KnownUrls) -> ImportReport {
+    let mut report = ImportReport::default();
+    let mut seen = HashSet::new();
+
+    for (index, line) in text.trim_start_matches('\u{feff}').lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let candidates = extract_candidates(line);
+        if candidates.is_empty() {
+            report.entries.push(ImportEntry {
+                line: index + 1,
+                input: line.to_string(),
+                url: None,
+                status: ImportStatus::Invalid,
+                message: Some(AppError::InvalidUrl(line.to_string()).user_message()),
+            });
+            continue;
+        }
+
+        for candidate in candidates {
+            let (url, status, message) = match normalize_video_url(candidate) {
+                Ok(normalized) => {
+                    let status = classify(&normalized, known, &mut seen);
+                    if status == ImportStatus::Ok {
+                        report.urls.push(normalized.url.clone());
+                    }
+                    (Some(normalized.url), status, None)
+                }
+                Err(AppError::PlaylistNotSupported(url)) => {
+                    let message = AppError::PlaylistNotSupported(url.clone()).user_message();
+                    (Some(url), ImportStatus::Playlist, Some(message))
+                }
+                Err(e) => (None, ImportStatus::Invalid, Some(e.user_message())),
+            };
+
+            report.entries.push(ImportEntry {
+                line: index + 1,
+                input: candidate.to_string(),
+                url,
+                status,
+                message,
+            });
+        }
+    }
+
+    report
+}
+
+fn classify(
+    normalized: &NormalizedUrl,
+    known: &KnownUrls,
+    seen: &mut HashSet<String>,
+) -> ImportStatus {
+    let key = normalized.key();
+    if known.in_history(normalized) {
+        ImportStatus::InHistory
+    } else if known.queue.contains(&key) {
+        ImportStatus::InQueue
+    } else if !seen.insert(key) {
+        ImportStatus::Duplicate
+    } else {
+        ImportStatus::Ok
+    }
+}
+
+/// 提取一行中的候选链接
+fn extract_candidates(line: &str) -> Vec<&str> {
+    let tokens: Vec<&str> = line
+        .split(|c: char| c.is_whitespace() || SEPARATORS.contains(&c))
+        .filter(|token| !token.is_empty())
+        .collect();
+
+    // 整行只有一个词时交给规范化判断（如视频 ID、省略协议的链接）
+    if let [token] = tokens.as_slice() {
+        return vec![token.trim_end_matches(TRAILING_PUNCTUATION)];
+    }
+
+    tokens
+        .into_iter()
+        .filter_map(|token| {
+            let start = token.find("https://").or_else(|| token.find("http://"))?;
+            Some(token[start..].trim_end_matches(TRAILING_PUNCTUATION))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statuses(report: &ImportReport) -> Vec<(usize, ImportStatus)> {
+        report.entries.iter().map(|e| (e.line, e.status)).collect()
+    }
+
+    #[test]
+    fn test_extract_candidates() {
+        assert_eq!(
+            extract_candidates("看这个: https://youtu.be/dQw4w9WgXcQ, 还有 (https://vimeo.com/1)."),
+            vec!["https://youtu.be/dQw4w9WgXcQ", "https://vimeo.com/1"]
+        );
+        assert_eq!(
+            extract_candidates("\"标题\",\"https://www.bilibili.com/video/BV1xx\",123"),
+            vec!["https://www.bilibili.com/video/BV1xx"]
+        );
+        assert_eq!(extract_candidates("dQw4w9WgXcQ"), vec!["dQw4w9WgXcQ"]);
+        assert!(extract_candidates("downloading the videos").is_empty());
+    }
+
+    #[test]
+    fn test_report_per_line() {
+        let text = "\u{feff}https://www.youtube.com/watch?v=dQw4w9WgXcQ\n\
+                    \n\
+                    https://youtu.be/dQw4w9WgXcQ?si=share\n\
+                    https://youtu.be/aaaaaaaaaaa https://youtu.be/bbbbbbbbbbb\n\
+                    https://www.youtube.com/playlist?list=PL123\n\
+                    not a link here\n\
+                    ftp://example.com/video\n\
+                    https://vimeo.com/42?utm_source=share";
+        let known = KnownUrls::new(
+            ["https://youtu.be/aaaaaaaaaaa"],
+            ["https://www.youtube.com/watch?v=bbbbbbbbbbb&feature=share"],
+        );
+
+        let report = build_report(text, &known);
+        assert_eq!(
+            statuses(&report),
+            vec![
+                (1, ImportStatus::Ok),
+                (3, ImportStatus::Duplicate),
+                (4, ImportStatus::InHistory),
+                (4, ImportStatus::InQueue),
+                (5, ImportStatus::Playlist),
+                (6, ImportStatus::Invalid),
+                (7, ImportStatus::Invalid),
+                (8, ImportStatus::Ok),
+            ]
+        );
+        assert_eq!(
+            report.urls,
+            vec![
+                "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+                "https://vimeo.com/42"
+            ]
+        );
+        assert_eq!(report.entries[4].url.as_deref(), Some("https://www.youtube.com/playlist?list=PL123"));
+        assert!(report.entries[5].message.is_some());
+        assert!(report.entries[0].message.is_none());
+    }
+
+    #[test]
+    fn test_single_words_are_not_video_ids() {
+        // 11 个字母的单词和 CSV 表头不是视频 ID
+        let text = "description\ntitle,url\nvideo_title\ndQw4w9WgXcQ\naBc_dEf-gHi";
+        let report = build_report(text, &KnownUrls::default());
+        assert_eq!(
+            statuses(&report),
+            vec![
+                (1, ImportStatus::Invalid),
+                (2, ImportStatus::Invalid),
+                (3, ImportStatus::Invalid),
+                (4, ImportStatus::Ok),
+                (5, ImportStatus::Ok),
+            ]
+        );
+    }
+}
@@ -1,19 +1,153 @@
 use crate::error::AppError;
-use crate::models::{Format, ProgressData, VideoInfo};
+use crate::models::{AppConfig, Format, ProgressData, VideoInfo};
+use crate::services::info_cache::InfoCache;
+use crate::services::task_output::{OutputStream, TaskOutput};
+use crate::utils::fs::format_size;
+use crate::utils::i18n::{tr, Text};
+use crate::utils::url::normalize_video_url;
 use regex::Regex;
 use serde_json::Value;
 use std::process::{Command, Stdio};
 use std::sync::Arc;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command as TokioCommand;
-use tokio::sync::Mutex;
+use tokio::sync::{watch, Mutex};
+
+/// yt-dlp 内部重试次数（`--retries` / `--fragment-retries`）
+const YTDLP_RETRIES: &str = "10";
+
+/// 视频已在下载存档中时 yt-dlp 输出的提示（此时跳过下载并正常退出）
+const ARCHIVE_SKIP_MARKER: &str = "has already been recorded in the archive";
+
+/// yt-dlp 运行选项（获取信息和下载时共用）
+#[derive(Debug, Clone, Default)]
+pub struct YtdlpOptions {
+    /// 代理 URL（`--proxy`）
+    pub proxy: Option<String>,
+    /// 下载限速（`--limit-rate`）
+    pub rate_limit: Option<String>,
+    /// 续传已下载的部分（`--continue`），否则强制覆盖重新下载
+    pub resume: bool,
+    /// 保留已下载过的同名文件，新文件名后加上任务标识
+    pub keep_both: bool,
+    /// 下载存档文件（`--download-archive`），用户明确选择重新下载时不使用
+    pub download_archive: Option<String>,
+}
+
+impl YtdlpOptions {
+    /// 根据应用配置构造运行选项
+    ///
+    /// 限速由下载管理器在任务启动时按时段规则计算，这里不设置
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            proxy: config.proxy_url(),
+            download_archive: config
+                .download_archive
+                .clone()
+                .filter(|path| !path.trim().is_empty()),
+            ..Self::default()
+        }
+    }
+
+    /// 转换为 yt-dlp 命令行参数
+    fn to_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(proxy) = &self.proxy {
+            args.push("--proxy".to_string());
+            args.push(proxy.clone());
+        }
+        args
+    }
+
+    /// 转换为下载专用的 yt-dlp 命令行参数
+    fn to_download_args(&self) -> Vec<String> {
+        let mut args = self.to_args();
+        // 由 yt-dlp 先处理单个请求和分片的短暂失败
+        args.extend(
+            ["--retries", YTDLP_RETRIES, "--fragment-retries", YTDLP_RETRIES].map(String::from),
+        );
+        if let Some(rate_limit) = &self.rate_limit {
+            args.push("--limit-rate".to_string());
+            args.push(rate_limit.clone());
+        }
+        if let Some(archive) = &self.download_archive {
+            args.push("--download-archive".to_string());
+            args.push(archive.clone());
+        }
+        if self.resume {
+            args.push("--continue".to_string());
+        } else {
+            // 强制覆盖已存在的文件（同时不续传残留的 .part 文件）
+            args.push("--force-overwrites".to_string());
+        }
+        args
+    }
+}
+
+/// 下载任务控制信号
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskSignal {
+    /// 正常运行
+    Run,
+    /// 取消下载
+    Cancel,
+    /// 终止当前进程并以新参数续传（如调整限速）
+    Restart,
+    /// 目标磁盘空间不足，终止下载
+    DiskFull,
+}
+
+/// 单次 yt-dlp 下载的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownloadOutcome {
+    /// 下载完成，包含最终的文件路径（无法从输出中识别时为空）
+    Completed(String),
+    /// 下载存档中已有该视频，yt-dlp 跳过了下载
+    Skipped,
+    /// 被控制信号中断
+    Interrupted(TaskSignal),
+}
+
+/// 下载前按所选格式解析出的视频信息（用于检查磁盘空间和记录下载历史）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DownloadProbe {
+    /// 视频 ID
+    pub video_id: String,
+    /// 视频标题
+    pub title: String,
+    /// 上传者
+    pub uploader: String,
+    /// 时长（秒）
+    pub duration: u32,
+    /// 缩略图 URL
+    pub thumbnail: String,
+    /// 提取器名称（小写，如 `youtube`）
+    pub extractor: String,
+    /// 所选格式的分辨率
+    pub resolution: String,
+    /// 所选格式的视频编码
+    pub vcodec: String,
+    /// 所选格式的音频编码
+    pub acodec: String,
+    /// 标签
+    pub tags: Vec<String>,
+    /// 所选格式的估算大小（字节），未知时为 `None`
+    pub size: Option<u64>,
+}
 
 /// yt-dlp 服务
 #[allow(dead_code)]
+#[derive(Clone)]
 pub struct YtdlpService {
     ytdlp_path: String,
     /// 存储正在运行的下载进程，用于取消操作
     active_downloads: Arc<Mutex<std::collections::HashMap<String, tokio::process::Child>>>,
+    /// 运行选项
+    options: YtdlpOptions,
+    /// 下载输出记录
+    output: Option<TaskOutput>,
+    /// 视频信息缓存
+    info_cache: Option<InfoCache>,
 }
 
 impl YtdlpService {
@@ -27,17 +161,17 @@ impl YtdlpService {
             
             if let Some(cwd_path) = &cwd {
                 let path = cwd_path.join("yt-dlp.exe");
-                eprintln!("检查当前工作目录: {:?}", path);
+                log::debug!("检查当前工作目录: {:?}", path);
                 if path.exists() {
-                    eprintln!("✓ 找到 yt-dlp");
+                    log::debug!("✓ 找到 yt-dlp");
                     found_path = Some(path.to_string_lossy().to_string());
                 } else {
                     // 2. 尝试父目录（开发环境下，当前目录可能是 src-tauri）
                     if let Some(parent) = cwd_path.parent() {
                         let parent_path = parent.join("yt-dlp.exe");
-                        eprintln!("检查父目录: {:?}", parent_path);
+                        log::debug!("检查父目录: {:?}", parent_path);
                         if parent_path.exists() {
-                            eprintln!("✓ 在父目录找到 yt-dlp");
+                            log::debug!("✓ 在父目录找到 yt-dlp");
                             found_path = Some(parent_path.to_string_lossy().to_string());
                         }
                     }
@@ -49,9 +183,9 @@ impl YtdlpService {
                 if let Ok(exe_path) = std::env::current_exe() {
                     if let Some(exe_dir) = exe_path.parent() {
                         let local_path = exe_dir.join("yt-dlp.exe");
-                        eprintln!("检查程序目录: {:?}", local_path);
+                        log::debug!("检查程序目录: {:?}", local_path);
                         if local_path.exists() {
-                            eprintln!("✓ 在程序目录找到 yt-dlp");
+                            log::debug!("✓ 在程序目录找到 yt-dlp");
                             found_path = Some(local_path.to_string_lossy().to_string());
                         }
                     }
@@ -64,7 +198,7 @@ impl YtdlpService {
         };
 
         // 验证 yt-dlp 是否可执行
-        eprintln!("尝试使用 yt-dlp 路径: {}", ytdlp_path);
+        log::debug!("尝试使用 yt-dlp 路径: {}", ytdlp_path);
         let output = Command::new(&ytdlp_path)
             .arg("--version")
             .output();
@@ -73,103 +207,228 @@ impl YtdlpService {
             Ok(out) => {
                 if out.status.success() {
                     let version = String::from_utf8_lossy(&out.stdout);
-                    eprintln!("yt-dlp 版本: {}", version.trim());
+                    log::info!("yt-dlp 版本: {}", version.trim());
                     Ok(Self {
                         ytdlp_path: ytdlp_path.to_string(),
                         active_downloads: Arc::new(Mutex::new(std::collections::HashMap::new())),
+                        options: YtdlpOptions::default(),
+                        output: None,
+                        info_cache: None,
                     })
                 } else {
                     let stderr = String::from_utf8_lossy(&out.stderr);
-                    eprintln!("yt-dlp 执行失败: {}", stderr);
+                    log::warn!("yt-dlp 执行失败: {}", stderr);
                     Err(AppError::YtdlpNotFound)
                 }
             }
             Err(e) => {
-                eprintln!("无法执行 yt-dlp: {}", e);
+                log::warn!("无法执行 yt-dlp: {}", e);
                 Err(AppError::YtdlpNotFound)
             }
         }
     }
 
+    /// 设置运行选项
+    pub fn with_options(mut self, options: YtdlpOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// 获取运行选项
+    pub fn options(&self) -> &YtdlpOptions {
+        &self.options
+    }
+
+    /// 设置下载输出记录，下载时写入命令行和 yt-dlp 的全部输出
+    pub fn with_output(mut self, output: TaskOutput) -> Self {
+        self.output = Some(output);
+        self
+    }
+
+    /// 设置视频信息缓存
+    ///
+    /// 获取信息时优先读取缓存；缓存未过期时，估算大小和下载通过 `--load-info-json` 复用缓存，
+    /// 不再重新解析网页
+    pub fn with_info_cache(mut self, info_cache: InfoCache) -> Self {
+        self.info_cache = Some(info_cache);
+        self
+    }
+
+    /// 视频来源参数：缓存未过期时使用 `--load-info-json`，否则使用 URL
+    fn source_args(&self, url: &str) -> (Vec<String>, bool) {
+        let cached = self.info_cache.as_ref().and_then(|cache| cache.fresh_path(url));
+        match cached {
+            Some(path) => (
+                vec![
+                    "--load-info-json".to_string(),
+                    path.to_string_lossy().into_owned(),
+                ],
+                true,
+            ),
+            None => (vec![url.to_string()], false),
+        }
+    }
+
     /// 获取视频信息
     pub async fn fetch_info(&self, url: &str) -> Result<VideoInfo, AppError> {
-        eprintln!("[fetch_info] 开始获取视频信息: {}", url);
+        log::info!("[fetch_info] 开始获取视频信息: {}", url);
+        
+        // 规范化链接，去掉跟踪参数；播放列表和频道链接不支持
+        let normalized = normalize_video_url(url)?;
+        let url = normalized.url.as_str();
+
+        let cached = self.info_cache.as_ref().and_then(|cache| cache.get(url));
+        let from_cache = cached.is_some();
+        let stdout = match cached {
+            Some(json) => {
+                log::debug!("[fetch_info] 使用缓存的视频信息");
+                json
+            }
+            None => {
+                let json = self.dump_json(url, Vec::new()).await?;
+                log::debug!("[fetch_info] yt-dlp 执行成功,开始解析 JSON...");
+                json
+            }
+        };
         
-        // 验证 URL 基本格式
-        if !url.starts_with("http://") && !url.starts_with("https://") {
-            eprintln!("[fetch_info] URL 格式无效");
-            return Err(AppError::InvalidUrl(url.to_string()));
+        // 解析 JSON 输出
+        let result = self.parse_video_info(&stdout);
+        
+        match &result {
+            Ok(info) => log::debug!("[fetch_info] 解析成功: {}", info.title),
+            Err(e) => log::warn!("[fetch_info] 解析失败: {:?}", e),
+        }
+        
+        // 只缓存新获取的信息，重写缓存会刷新修改时间，使缓存永远不会过期
+        if let (Ok(_), false, Some(cache)) = (&result, from_cache, &self.info_cache) {
+            if let Err(e) = cache.put(url, &stdout) {
+                log::warn!("[fetch_info] 无法写入视频信息缓存: {:?}", e);
+            }
         }
 
-        eprintln!("[fetch_info] 使用 yt-dlp 路径: {}", self.ytdlp_path);
-        eprintln!("[fetch_info] 启动 spawn_blocking 任务...");
+        result
+    }
+
+    /// 执行 `yt-dlp --dump-json`，返回原始 JSON 输出
+    async fn dump_json(&self, url: &str, extra_args: Vec<String>) -> Result<String, AppError> {
+        log::debug!("[dump_json] 使用 yt-dlp 路径: {}", self.ytdlp_path);
+        log::debug!("[dump_json] 启动 spawn_blocking 任务...");
         
         // 调用 yt-dlp --dump-json 获取视频信息
         let output = tokio::task::spawn_blocking({
             let ytdlp_path = self.ytdlp_path.clone();
-            let url = url.to_string();
+            let (source_args, _) = self.source_args(url);
+            let option_args = self.options.to_args();
             move || {
-                eprintln!("[spawn_blocking] 执行 yt-dlp 命令...");
+                log::debug!("[spawn_blocking] 执行 yt-dlp 命令...");
                 let result = Command::new(&ytdlp_path)
                     .arg("--dump-json")
                     .arg("--no-playlist")
-                    .arg(&url)
+                    .args(&option_args)
+                    .args(&extra_args)
+                    .args(&source_args)
                     .output();
-                eprintln!("[spawn_blocking] 命令执行完成");
+                log::debug!("[spawn_blocking] 命令执行完成");
                 result
             }
         })
         .await
         .map_err(|e| {
-            eprintln!("[fetch_info] spawn_blocking 任务失败: {}", e);
-            AppError::YtdlpError(format!("任务执行失败: {}", e))
+            log::warn!("[dump_json] spawn_blocking 任务失败: {}", e);
+            AppError::YtdlpError(tr(Text::ProcessFailed, &[&e]))
         })?
         .map_err(|e| {
-            eprintln!("[fetch_info] 进程启动失败: {}", e);
-            AppError::YtdlpError(format!("进程启动失败: {}", e))
+            log::warn!("[dump_json] 进程启动失败: {}", e);
+            AppError::YtdlpError(tr(Text::ProcessFailed, &[&e]))
         })?;
 
         // 检查是否执行成功
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            eprintln!("[fetch_info] yt-dlp 执行失败: {}", stderr);
+            log::warn!("[dump_json] yt-dlp 执行失败: {}", stderr);
             
             // 检查是否是不支持的网站
             if stderr.contains("Unsupported URL") || stderr.contains("not supported") {
                 return Err(AppError::UnsupportedSite(url.to_string()));
             }
             
-            return Err(AppError::YtdlpError(stderr.to_string()));
+            return Err(Self::classify_error(&stderr));
         }
 
-        eprintln!("[fetch_info] yt-dlp 执行成功,开始解析 JSON...");
-        
-        // 解析 JSON 输出
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let result = self.parse_video_info(&stdout);
-        
-        match &result {
-            Ok(info) => eprintln!("[fetch_info] 解析成功: {}", info.title),
-            Err(e) => eprintln!("[fetch_info] 解析失败: {:?}", e),
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// 按所选格式解析视频信息，包括下载所需的空间
+    pub async fn probe_download(&self, url: &str, format_id: &str) -> Result<DownloadProbe, AppError> {
+        let stdout = self
+            .dump_json(url, vec!["--format".to_string(), format_id.to_string()])
+            .await?;
+        let json: Value = serde_json::from_str(&stdout)
+            .map_err(|e| AppError::ParseError(tr(Text::InfoJsonInvalid, &[&e])))?;
+        Ok(self.parse_probe(&json))
+    }
+
+    /// 从选定格式后的 JSON 输出中提取视频信息
+    fn parse_probe(&self, json: &Value) -> DownloadProbe {
+        let text = |key: &str| json[key].as_str().unwrap_or_default().to_string();
+        let extractor = json["extractor_key"]
+            .as_str()
+            .or_else(|| json["extractor"].as_str())
+            .unwrap_or_default()
+            .to_lowercase();
+
+        DownloadProbe {
+            video_id: text("id"),
+            title: text("title"),
+            uploader: text("uploader"),
+            duration: json["duration"].as_f64().unwrap_or(0.0) as u32,
+            thumbnail: text("thumbnail"),
+            extractor,
+            resolution: self.parse_resolution(json),
+            vcodec: text("vcodec"),
+            acodec: text("acodec"),
+            tags: json["tags"]
+                .as_array()
+                .map(|tags| {
+                    tags.iter()
+                        .filter_map(|tag| tag.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            size: Self::selected_formats_size(json),
+        }
+    }
+
+    /// 计算 yt-dlp 选中格式的总大小
+    ///
+    /// 合并下载（如 `137+140`）时各格式位于 `requested_formats`，
+    /// 任一格式大小未知时返回 `None`
+    fn selected_formats_size(json: &Value) -> Option<u64> {
+        let format_size =
+            |f: &Value| f["filesize"].as_u64().or_else(|| f["filesize_approx"].as_u64());
+
+        match json["requested_formats"].as_array() {
+            Some(formats) if !formats.is_empty() => {
+                formats.iter().map(format_size).sum::<Option<u64>>()
+            }
+            _ => format_size(json),
         }
-        
-        result
     }
 
     /// 解析 yt-dlp 的 JSON 输出
     fn parse_video_info(&self, json_str: &str) -> Result<VideoInfo, AppError> {
         let json: Value = serde_json::from_str(json_str)
-            .map_err(|e| AppError::ParseError(format!("JSON 解析失败: {}", e)))?;
+            .map_err(|e| AppError::ParseError(tr(Text::InfoJsonInvalid, &[&e])))?;
 
         // 提取基本信息
         let id = json["id"]
             .as_str()
-            .ok_or_else(|| AppError::ParseError("缺少视频 ID".to_string()))?
+            .ok_or_else(|| AppError::ParseError(tr(Text::VideoIdMissing, &[])))?
             .to_string();
 
         let title = json["title"]
             .as_str()
-            .ok_or_else(|| AppError::ParseError("缺少视频标题".to_string()))?
+            .ok_or_else(|| AppError::ParseError(tr(Text::VideoTitleMissing, &[])))?
             .to_string();
 
         let duration = json["duration"]
@@ -190,7 +449,7 @@ impl YtdlpService {
         let formats = self.extract_formats(&json)?;
 
         if formats.is_empty() {
-            return Err(AppError::ParseError("没有可用的视频格式".to_string()));
+            return Err(AppError::ParseError(tr(Text::NoVideoFormats, &[])));
         }
 
         Ok(VideoInfo {
@@ -207,7 +466,7 @@ impl YtdlpService {
     fn extract_formats(&self, json: &Value) -> Result<Vec<Format>, AppError> {
         let formats_array = json["formats"]
             .as_array()
-            .ok_or_else(|| AppError::ParseError("缺少格式列表".to_string()))?;
+            .ok_or_else(|| AppError::ParseError(tr(Text::FormatListMissing, &[])))?;
 
         let mut formats = Vec::new();
 
@@ -379,21 +638,8 @@ impl YtdlpService {
     #[allow(dead_code)]
     pub fn format_filesize(&self, filesize: Option<u64>) -> String {
         match filesize {
-            Some(size) => {
-                if size >= 1_073_741_824 {
-                    // >= 1 GB
-                    format!("{:.2} GB", size as f64 / 1_073_741_824.0)
-                } else if size >= 1_048_576 {
-                    // >= 1 MB
-                    format!("{:.2} MB", size as f64 / 1_048_576.0)
-                } else if size >= 1024 {
-                    // >= 1 KB
-                    format!("{:.2} KB", size as f64 / 1024.0)
-                } else {
-                    format!("{} B", size)
-                }
-            }
-            None => "未知大小".to_string(),
+            Some(size) => format_size(size),
+            None => tr(Text::UnknownSize, &[]),
         }
     }
 
@@ -406,9 +652,11 @@ impl YtdlpService {
     /// - `format_id`: 格式 ID
     /// - `save_path`: 保存路径（包含文件名）
     /// - `progress_callback`: 进度回调函数
+    /// - `control`: 控制信号接收端，收到取消或重启信号时终止 yt-dlp 进程
     /// 
     /// # 返回
-    /// - `Ok(String)`: 下载完成，返回文件路径
+    /// - `Ok(DownloadOutcome::Completed)`: 下载完成，包含文件路径
+    /// - `Ok(DownloadOutcome::Interrupted)`: 被控制信号中断
     /// - `Err(AppError)`: 下载失败
     pub async fn download_video<F>(
         &self,
@@ -417,73 +665,91 @@ impl YtdlpService {
         format_id: String,
         save_path: String,
         progress_callback: F,
-    ) -> Result<String, AppError>
+        mut control: watch::Receiver<TaskSignal>,
+    ) -> Result<DownloadOutcome, AppError>
     where
         F: Fn(ProgressData) + Send + 'static,
     {
-        eprintln!("[download_video] 开始下载:");
-        eprintln!("  - task_id: {}", task_id);
-        eprintln!("  - url: {}", url);
-        eprintln!("  - format_id: {}", format_id);
-        eprintln!("  - save_path: {}", save_path);
+        log::info!(
+            task_id = task_id.as_str();
+            "[download_video] 开始下载: url={}, format_id={}, save_path={}",
+            url,
+            format_id,
+            save_path
+        );
         
         // 构造输出路径模板
         // save_path 是目录路径，需要添加文件名模板
         // 使用 yt-dlp 的文件名清理功能，限制文件名长度为 200 字符
+        // 保留已有文件时文件名后加上任务标识（重试和续传时保持不变）
+        let file_name = if self.options.keep_both {
+            format!("%(title).200B-{}.%(ext)s", Self::task_file_suffix(&task_id))
+        } else {
+            "%(title).200B.%(ext)s".to_string()
+        };
         let output_template = if save_path.ends_with('\\') || save_path.ends_with('/') {
-            format!("{}%(title).200B.%(ext)s", save_path)
+            format!("{}{}", save_path, file_name)
         } else {
-            format!("{}\\%(title).200B.%(ext)s", save_path)
+            format!("{}\\{}", save_path, file_name)
         };
         
-        eprintln!("[download_video] 输出模板: {}", output_template);
+        log::debug!(task_id = task_id.as_str(); "[download_video] 输出模板: {}", output_template);
         
-        // 构造下载命令
+        // 构造下载命令参数
+        let mut args: Vec<String> = vec![
+            "--format".to_string(),
+            format_id.clone(),
+            "--output".to_string(),
+            output_template,
+            "--restrict-filenames".to_string(), // 限制文件名只使用 ASCII 字符
+            "--newline".to_string(),            // 每行输出进度信息
+            "--no-playlist".to_string(),
+        ];
+        // 使用 .part 临时文件，重启任务时可以用 --continue 续传
+        args.extend(self.options.to_download_args());
+        let (source_args, from_cache) = self.source_args(&url);
+        args.extend(source_args);
+
+        if let Some(output) = &self.output {
+            output.push_command(&self.ytdlp_path, &args);
+        }
+
         let mut cmd = TokioCommand::new(&self.ytdlp_path);
-        cmd.arg("--format")
-            .arg(&format_id)
-            .arg("--output")
-            .arg(&output_template)
-            .arg("--no-part")  // 不使用 .part 文件,直接下载
-            .arg("--force-overwrites")  // 强制覆盖已存在的文件
-            .arg("--restrict-filenames")  // 限制文件名只使用 ASCII 字符
-            .arg("--newline") // 每行输出进度信息
-            .arg("--no-playlist")
-            .arg(&url)
+        cmd.args(&args)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
-        eprintln!("[download_video] 启动 yt-dlp 进程...");
+        log::debug!(task_id = task_id.as_str(); "[download_video] 启动 yt-dlp 进程...");
         
         // 启动进程
         let mut child = cmd.spawn()
             .map_err(|e| {
-                eprintln!("[download_video] 启动进程失败: {}", e);
-                AppError::YtdlpError(format!("启动下载进程失败: {}", e))
+                log::error!(task_id = task_id.as_str(); "[download_video] 启动进程失败: {}", e);
+                AppError::YtdlpError(tr(Text::ProcessFailed, &[&e]))
             })?;
         
-        eprintln!("[download_video] 进程已启动");
+        log::debug!(task_id = task_id.as_str(); "[download_video] 进程已启动");
 
         // 获取 stdout 和 stderr（在移动 child 之前）
         let stdout = child.stdout.take()
             .ok_or_else(|| {
-                eprintln!("[download_video] 无法获取进程输出");
-                AppError::YtdlpError("无法获取进程输出".to_string())
+                log::warn!(task_id = task_id.as_str(); "[download_video] 无法获取进程输出");
+                AppError::YtdlpError(tr(Text::ProcessOutputUnavailable, &[]))
             })?;
 
         let stderr = child.stderr.take()
             .ok_or_else(|| {
-                eprintln!("[download_video] 无法获取进程错误输出");
-                AppError::YtdlpError("无法获取进程错误输出".to_string())
+                log::warn!(task_id = task_id.as_str(); "[download_video] 无法获取进程错误输出");
+                AppError::YtdlpError(tr(Text::ProcessOutputUnavailable, &[]))
             })?;
 
-        eprintln!("[download_video] 已获取进程输出流");
+        log::debug!(task_id = task_id.as_str(); "[download_video] 已获取进程输出流");
         
         // 保存进程引用以便取消
         {
             let mut downloads = self.active_downloads.lock().await;
             downloads.insert(task_id.clone(), child);
-            eprintln!("[download_video] 进程已保存到 active_downloads");
+            log::debug!(task_id = task_id.as_str(); "[download_video] 进程已保存到 active_downloads");
         }
 
         // 创建异步读取器
@@ -491,35 +757,50 @@ impl YtdlpService {
         let stderr_reader = BufReader::new(stderr);
 
         let task_id_clone = task_id.clone();
+        let stderr_task_id = task_id.clone();
+        let stdout_output = self.output.clone();
+        let stderr_output = self.output.clone();
 
         // 在独立任务中读取进度输出
         let progress_handle = tokio::spawn(async move {
-            eprintln!("[download_video] 开始读取进度输出...");
+            log::debug!(task_id = task_id_clone.as_str(); "[download_video] 开始读取进度输出...");
             let mut lines = stdout_reader.lines();
             let mut line_count = 0;
+            let mut skipped = false;
+            let mut file_path = None;
             while let Ok(Some(line)) = lines.next_line().await {
                 line_count += 1;
-                eprintln!("[download_video] stdout line {}: {}", line_count, line);
                 if let Some(progress) = Self::parse_progress(&task_id_clone, &line) {
                     progress_callback(progress);
                 }
+                skipped |= Self::is_archive_skip(&line);
+                // 依次输出各分段的目标文件和合并后的文件，最后一个即为最终文件
+                if let Some(path) = Self::parse_output_path(&line) {
+                    file_path = Some(path);
+                }
+                if let Some(output) = &stdout_output {
+                    output.push(OutputStream::Stdout, line);
+                }
             }
-            eprintln!("[download_video] 进度输出读取完成,共 {} 行", line_count);
+            log::debug!(task_id = task_id_clone.as_str(); "[download_video] 进度输出读取完成,共 {} 行", line_count);
+            (skipped, file_path)
         });
 
         // 读取错误输出
         let stderr_handle = tokio::spawn(async move {
-            eprintln!("[download_video] 开始读取错误输出...");
+            log::debug!(task_id = stderr_task_id.as_str(); "[download_video] 开始读取错误输出...");
             let mut errors = String::new();
             let mut stderr_lines = stderr_reader.lines();
             let mut line_count = 0;
             while let Ok(Some(line)) = stderr_lines.next_line().await {
                 line_count += 1;
-                eprintln!("[download_video] stderr line {}: {}", line_count, line);
                 errors.push_str(&line);
                 errors.push('\n');
+                if let Some(output) = &stderr_output {
+                    output.push(OutputStream::Stderr, line);
+                }
             }
-            eprintln!("[download_video] 错误输出读取完成,共 {} 行", line_count);
+            log::debug!(task_id = stderr_task_id.as_str(); "[download_video] 错误输出读取完成,共 {} 行", line_count);
             errors
         });
 
@@ -528,36 +809,169 @@ impl YtdlpService {
             let mut downloads = self.active_downloads.lock().await;
             downloads.remove(&task_id)
                 .ok_or_else(|| {
-                    eprintln!("[download_video] 任务不存在: {}", task_id);
+                    log::warn!(task_id = task_id.as_str(); "[download_video] 任务不存在");
                     AppError::TaskNotFound(task_id.clone())
                 })?
         };
 
-        eprintln!("[download_video] 等待进程完成...");
+        log::debug!(task_id = task_id.as_str(); "[download_video] 等待进程完成...");
         
-        // 等待进程完成
-        let status = child.wait().await
-            .map_err(|e| {
-                eprintln!("[download_video] 等待进程失败: {}", e);
-                AppError::YtdlpError(format!("等待进程失败: {}", e))
-            })?;
+        // 等待进程完成，期间收到控制信号则终止进程
+        let status = tokio::select! {
+            status = child.wait() => status.map_err(|e| {
+                log::warn!(task_id = task_id.as_str(); "[download_video] 等待进程失败: {}", e);
+                AppError::YtdlpError(tr(Text::ProcessFailed, &[&e]))
+            })?,
+            signal = Self::wait_for_signal(&mut control) => {
+                log::info!(task_id = task_id.as_str(); "[download_video] 收到控制信号 {:?}，终止进程", signal);
+                child.kill().await
+                    .map_err(|e| AppError::YtdlpError(tr(Text::ProcessFailed, &[&e])))?;
+                let _ = progress_handle.await;
+                let _ = stderr_handle.await;
+                return Ok(DownloadOutcome::Interrupted(signal));
+            }
+        };
 
-        eprintln!("[download_video] 进程已完成,状态: {:?}", status);
+        log::debug!(task_id = task_id.as_str(); "[download_video] 进程已完成,状态: {:?}", status);
         
         // 等待进度读取完成
-        let _ = progress_handle.await;
+        let (skipped, file_path) = progress_handle.await.unwrap_or_default();
         
         // 获取错误输出
         let error_output = stderr_handle.await
             .unwrap_or_else(|_| String::new());
 
         // 检查下载是否成功
-        if status.success() {
-            eprintln!("[download_video] 下载成功: {}", save_path);
-            Ok(save_path)
+        if status.success() && skipped {
+            log::info!(task_id = task_id.as_str(); "[download_video] 下载存档中已有该视频，已跳过");
+            Ok(DownloadOutcome::Skipped)
+        } else if status.success() {
+            let file_path = file_path.unwrap_or_else(|| {
+                log::warn!(task_id = task_id.as_str(); "[download_video] 无法从输出中识别下载的文件");
+                String::new()
+            });
+            log::info!(task_id = task_id.as_str(); "[download_video] 下载成功: {}", file_path);
+            Ok(DownloadOutcome::Completed(file_path))
+        } else {
+            log::warn!(task_id = task_id.as_str(); "[download_video] 下载失败: {}", error_output);
+            // 缓存中的媒体地址可能已失效，重试时重新获取
+            if let (true, Some(cache)) = (from_cache, &self.info_cache) {
+                cache.invalidate(&url);
+            }
+            Err(Self::classify_error(&error_output))
+        }
+    }
+
+    /// 根据 yt-dlp 的错误输出归类错误
+    ///
+    /// 无法识别的错误保留原始输出
+    pub fn classify_error(stderr: &str) -> AppError {
+        let lower = stderr.to_lowercase();
+        let contains_any = |patterns: &[&str]| patterns.iter().any(|p| lower.contains(p));
+
+        if contains_any(&[
+            "no space left on device",
+            "errno 28",
+            "not enough space on the disk",
+        ]) {
+            AppError::InsufficientSpace
+        } else if contains_any(&["private video", "video is private"]) {
+            AppError::PrivateVideo
+        } else if contains_any(&["sign in to confirm", "login required", "use --cookies"]) {
+            AppError::SignInRequired
+        } else if contains_any(&[
+            "available in your country",
+            "geo restriction",
+            "geo-restricted",
+        ]) {
+            AppError::GeoBlocked
+        } else if contains_any(&["http error 429", "too many requests"]) {
+            AppError::RateLimited
+        } else if lower.contains("http error 403") {
+            AppError::HttpForbidden
+        } else if contains_any(&["http error 404", "http error 410", "video unavailable"]) {
+            // 视频已删除或不存在，重试没有意义
+            AppError::VideoUnavailable(Self::last_error_line(stderr))
+        } else if contains_any(&[
+            "connection reset",
+            "connection aborted",
+            "connection refused",
+            "remote end closed connection",
+            "network is unreachable",
+            "timed out",
+            "incompleteread",
+            "temporary failure in name resolution",
+            "urlopen error",
+        ]) {
+            AppError::NetworkInterrupted(Self::last_error_line(stderr))
         } else {
-            eprintln!("[download_video] 下载失败: {}", error_output);
-            Err(AppError::YtdlpError(format!("下载失败: {}", error_output)))
+            // yt-dlp 的原始输出不需要翻译
+            AppError::YtdlpError(stderr.trim().to_string())
+        }
+    }
+
+    /// 从 yt-dlp 输出中识别写入的文件路径
+    ///
+    /// 识别以下几种输出：
+    /// [download] Destination: /downloads/title.f137.mp4
+    /// [Merger] Merging formats into "/downloads/title.mp4"
+    /// [download] /downloads/title.mp4 has already been downloaded
+    /// [ExtractAudio] Destination: /downloads/title.mp3
+    fn parse_output_path(line: &str) -> Option<String> {
+        let path = if let Some(rest) = line.strip_prefix("[Merger] Merging formats into ") {
+            rest.trim().trim_matches('"')
+        } else if let Some(rest) = line.strip_prefix("[download] ") {
+            match rest.strip_prefix("Destination: ") {
+                Some(path) => path,
+                None => rest
+                    .strip_suffix(" has already been downloaded and merged")
+                    .or_else(|| rest.strip_suffix(" has already been downloaded"))?,
+            }
+        } else if line.starts_with('[') {
+            // 后处理步骤转换格式后的文件，如 [ExtractAudio]、[VideoConvertor]
+            line.split_once("] Destination: ")?.1
+        } else {
+            return None;
+        };
+        let path = path.trim();
+        (!path.is_empty()).then(|| path.to_string())
+    }
+
+    /// 是否为视频已在下载存档中、跳过下载的提示
+    fn is_archive_skip(line: &str) -> bool {
+        line.starts_with("[download]") && line.contains(ARCHIVE_SKIP_MARKER)
+    }
+
+    /// 取 yt-dlp 输出中的最后一条 ERROR 行
+    fn last_error_line(stderr: &str) -> String {
+        stderr
+            .lines()
+            .rev()
+            .find(|line| line.starts_with("ERROR"))
+            .or_else(|| stderr.lines().rev().find(|line| !line.trim().is_empty()))
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    }
+
+    /// 文件名后缀：取任务 ID 末尾的 8 个字母或数字
+    fn task_file_suffix(task_id: &str) -> String {
+        let chars: Vec<char> = task_id.chars().filter(char::is_ascii_alphanumeric).collect();
+        chars[chars.len().saturating_sub(8)..].iter().collect()
+    }
+
+    /// 等待非 Run 的控制信号
+    ///
+    /// 发送端被丢弃时永远不会返回
+    pub(crate) async fn wait_for_signal(control: &mut watch::Receiver<TaskSignal>) -> TaskSignal {
+        loop {
+            if control.changed().await.is_err() {
+                std::future::pending::<()>().await;
+            }
+            let signal = control.borrow_and_update().clone();
+            if signal != TaskSignal::Run {
+                return signal;
+            }
         }
     }
 
@@ -604,29 +1018,6 @@ impl YtdlpService {
 
         None
     }
-
-    /// 取消下载
-    /// 需求：3.5
-    /// 
-    /// # 参数
-    /// - `task_id`: 任务 ID
-    /// 
-    /// # 返回
-    /// - `Ok(())`: 取消成功
-    /// - `Err(AppError)`: 取消失败或任务不存在
-    pub async fn cancel_download(&self, task_id: &str) -> Result<(), AppError> {
-        let mut downloads = self.active_downloads.lock().await;
-        
-        if let Some(mut child) = downloads.remove(task_id) {
-            // 终止进程
-            child.kill().await
-                .map_err(|e| AppError::YtdlpError(format!("终止进程失败: {}", e)))?;
-            
-            Ok(())
-        } else {
-            Err(AppError::TaskNotFound(task_id.to_string()))
-        }
-    }
 }
 
 #[cfg(test)]
@@ -639,6 +1030,9 @@ mod tests {
         let service = YtdlpService {
             ytdlp_path: "yt-dlp".to_string(),
             active_downloads: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            options: YtdlpOptions::default(),
+            output: None,
+            info_cache: None,
         };
 
         let json = serde_json::json!({
@@ -655,6 +1049,9 @@ mod tests {
         let service = YtdlpService {
             ytdlp_path: "yt-dlp".to_string(),
             active_downloads: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            options: YtdlpOptions::default(),
+            output: None,
+            info_cache: None,
         };
 
         let json = serde_json::json!({
@@ -670,6 +1067,9 @@ mod tests {
         let service = YtdlpService {
             ytdlp_path: "yt-dlp".to_string(),
             active_downloads: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            options: YtdlpOptions::default(),
+            output: None,
+            info_cache: None,
         };
 
         let json = serde_json::json!({
@@ -685,6 +1085,9 @@ mod tests {
         let service = YtdlpService {
             ytdlp_path: "yt-dlp".to_string(),
             active_downloads: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            options: YtdlpOptions::default(),
+            output: None,
+            info_cache: None,
         };
 
         let json = serde_json::json!({});
@@ -731,6 +1134,9 @@ mod tests {
             let service = YtdlpService {
                 ytdlp_path: "yt-dlp".to_string(),
                 active_downloads: Arc::new(Mutex::new(std::collections::HashMap::new())),
+                options: YtdlpOptions::default(),
+                output: None,
+                info_cache: None,
             };
 
             // 生成格式列表
@@ -795,6 +1201,9 @@ mod tests {
         let service = YtdlpService {
             ytdlp_path: "yt-dlp".to_string(),
             active_downloads: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            options: YtdlpOptions::default(),
+            output: None,
+            info_cache: None,
         };
 
         let json_output = serde_json::json!({
@@ -812,7 +1221,7 @@ mod tests {
         assert!(result.is_err());
         match result {
             Err(AppError::ParseError(msg)) => {
-                assert!(msg.contains("没有可用的视频格式"));
+                assert_eq!(msg, tr(Text::NoVideoFormats, &[]));
             }
             _ => panic!("应该返回 ParseError"),
         }
@@ -824,6 +1233,9 @@ mod tests {
         let service = YtdlpService {
             ytdlp_path: "yt-dlp".to_string(),
             active_downloads: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            options: YtdlpOptions::default(),
+            output: None,
+            info_cache: None,
         };
 
         let json_output = serde_json::json!({
@@ -867,6 +1279,9 @@ mod tests {
         let service = YtdlpService {
             ytdlp_path: "yt-dlp".to_string(),
             active_downloads: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            options: YtdlpOptions::default(),
+            output: None,
+            info_cache: None,
         };
 
         // 测试 "1920x1080" 格式
@@ -894,6 +1309,9 @@ mod tests {
         let service = YtdlpService {
             ytdlp_path: "yt-dlp".to_string(),
             active_downloads: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            options: YtdlpOptions::default(),
+            output: None,
+            info_cache: None,
         };
 
         let formats = vec![
@@ -940,6 +1358,9 @@ mod tests {
         let service = YtdlpService {
             ytdlp_path: "yt-dlp".to_string(),
             active_downloads: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            options: YtdlpOptions::default(),
+            output: None,
+            info_cache: None,
         };
 
         let formats = vec![
@@ -973,6 +1394,9 @@ mod tests {
         let service = YtdlpService {
             ytdlp_path: "yt-dlp".to_string(),
             active_downloads: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            options: YtdlpOptions::default(),
+            output: None,
+            info_cache: None,
         };
 
         let formats = vec![
@@ -1007,6 +1431,9 @@ mod tests {
         let service = YtdlpService {
             ytdlp_path: "yt-dlp".to_string(),
             active_downloads: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            options: YtdlpOptions::default(),
+            output: None,
+            info_cache: None,
         };
 
         // 需要合并：有视频但无音频
@@ -1040,6 +1467,9 @@ mod tests {
         let service = YtdlpService {
             ytdlp_path: "yt-dlp".to_string(),
             active_downloads: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            options: YtdlpOptions::default(),
+            output: None,
+            info_cache: None,
         };
 
         // 测试 GB
@@ -1058,6 +1488,335 @@ mod tests {
         assert_eq!(service.format_filesize(None), "未知大小");
     }
 
+    #[test]
+    fn test_options_to_args() {
+        // 默认不添加任何参数
+        assert!(YtdlpOptions::default().to_args().is_empty());
+
+        let options = YtdlpOptions {
+            proxy: Some("socks5://127.0.0.1:1080".to_string()),
+            ..YtdlpOptions::default()
+        };
+        assert_eq!(options.to_args(), vec!["--proxy", "socks5://127.0.0.1:1080"]);
+    }
+
+    #[test]
+    fn test_options_to_download_args() {
+        // 首次下载：强制覆盖，不限速
+        assert_eq!(
+            YtdlpOptions::default().to_download_args(),
+            vec!["--retries", "10", "--fragment-retries", "10", "--force-overwrites"]
+        );
+
+        // 调整限速后续传
+        let options = YtdlpOptions {
+            rate_limit: Some("2M".to_string()),
+            resume: true,
+            ..YtdlpOptions::default()
+        };
+        assert_eq!(
+            options.to_download_args(),
+            vec![
+                "--retries",
+                "10",
+                "--fragment-retries",
+                "10",
+                "--limit-rate",
+                "2M",
+                "--continue"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_task_file_suffix() {
+        assert_eq!(YtdlpService::task_file_suffix("task-1700000000000-abc123def"), "bc123def");
+        assert_eq!(YtdlpService::task_file_suffix("t-1"), "t1");
+    }
+
+    #[test]
+    fn test_selected_formats_size() {
+        // 合并下载：各格式大小相加，filesize 缺失时使用 filesize_approx
+        let merged = serde_json::json!({
+            "requested_formats": [
+                {"format_id": "137", "filesize": 100_000_000u64},
+                {"format_id": "140", "filesize": null, "filesize_approx": 5_000_000u64}
+            ]
+        });
+        assert_eq!(YtdlpService::selected_formats_size(&merged), Some(105_000_000));
+
+        // 单一格式
+        let single = serde_json::json!({"format_id": "18", "filesize_approx": 42u64});
+        assert_eq!(YtdlpService::selected_formats_size(&single), Some(42));
+
+        // 任一格式大小未知
+        let unknown = serde_json::json!({
+            "requested_formats": [
+                {"format_id": "137", "filesize": 100u64},
+                {"format_id": "140"}
+            ]
+        });
+        assert_eq!(YtdlpService::selected_formats_size(&unknown), None);
+        assert_eq!(YtdlpService::selected_formats_size(&serde_json::json!({})), None);
+    }
+
+    #[test]
+    fn test_parse_probe() {
+        let service = YtdlpService {
+            ytdlp_path: "yt-dlp".to_string(),
+            active_downloads: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            options: YtdlpOptions::default(),
+            output: None,
+            info_cache: None,
+        };
+        let json = serde_json::json!({
+            "id": "dQw4w9WgXcQ",
+            "title": "Video",
+            "uploader": "Channel",
+            "duration": 212.5,
+            "thumbnail": "https://i.ytimg.com/vi/dQw4w9WgXcQ/maxresdefault.jpg",
+            "extractor": "youtube",
+            "extractor_key": "Youtube",
+            "resolution": "1920x1080",
+            "vcodec": "avc1.640028",
+            "acodec": "mp4a.40.2",
+            "tags": ["music", 1],
+            "requested_formats": [
+                {"format_id": "137", "filesize": 100u64},
+                {"format_id": "140", "filesize": 20u64}
+            ]
+        });
+
+        let probe = service.parse_probe(&json);
+        assert_eq!(probe.video_id, "dQw4w9WgXcQ");
+        assert_eq!(probe.duration, 212);
+        assert_eq!(probe.extractor, "youtube");
+        assert_eq!(probe.resolution, "1920x1080");
+        assert_eq!(probe.size, Some(120));
+        assert_eq!(probe.vcodec, "avc1.640028");
+        assert_eq!(probe.tags, vec!["music"]);
+
+        // 缺少的字段使用空值
+        let probe = service.parse_probe(&serde_json::json!({"format_note": "720p"}));
+        assert_eq!(probe.resolution, "720p");
+        assert!(probe.uploader.is_empty());
+        assert_eq!(probe.size, None);
+    }
+
+    #[test]
+    fn test_source_args_uses_cached_info() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let cache = InfoCache::new(temp_dir.path()).unwrap();
+        let service = YtdlpService {
+            ytdlp_path: "yt-dlp".to_string(),
+            active_downloads: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            options: YtdlpOptions::default(),
+            output: None,
+            info_cache: Some(cache.clone()),
+        };
+        let url = "https://www.youtube.com/watch?v=dQw4w9WgXcQ";
+
+        assert_eq!(service.source_args(url), (vec![url.to_string()], false));
+
+        cache.put(url, "{}").unwrap();
+        let (args, from_cache) = service.source_args(url);
+        assert!(from_cache);
+        assert_eq!(args[0], "--load-info-json");
+        assert!(args[1].ends_with("youtube_dQw4w9WgXcQ.json"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_info_from_cache_keeps_expiry() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let cache = InfoCache::new(temp_dir.path()).unwrap();
+        let service = YtdlpService {
+            ytdlp_path: "yt-dlp-not-installed".to_string(),
+            active_downloads: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            options: YtdlpOptions::default(),
+            output: None,
+            info_cache: Some(cache.clone()),
+        };
+        let url = "https://www.youtube.com/watch?v=dQw4w9WgXcQ";
+        let json = serde_json::json!({
+            "id": "dQw4w9WgXcQ",
+            "title": "Cached",
+            "duration": 212,
+            "formats": [{"format_id": "22", "vcodec": "avc1", "acodec": "mp4a", "ext": "mp4",
+                         "width": 1280, "height": 720}]
+        });
+        cache.put(url, &json.to_string()).unwrap();
+        let path = cache.fresh_path(url).unwrap();
+        let written = std::time::SystemTime::now() - std::time::Duration::from_secs(600);
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(written)
+            .unwrap();
+
+        // 读取缓存不会重写缓存文件，过期时间仍从首次获取时算起
+        let info = service.fetch_info(url).await.unwrap();
+        assert_eq!(info.title, "Cached");
+        assert_eq!(std::fs::metadata(&path).unwrap().modified().unwrap(), written);
+    }
+
+    #[test]
+    fn test_classify_error() {
+        let cases = [
+            (
+                "ERROR: unable to download video data: HTTP Error 403: Forbidden",
+                "HttpForbidden",
+            ),
+            ("ERROR: HTTP Error 429: Too Many Requests", "RateLimited"),
+            (
+                "ERROR: [generic] abc: Unable to download webpage: HTTP Error 404: Not Found",
+                "VideoUnavailable",
+            ),
+            (
+                "ERROR: [vimeo] 1: Unable to download webpage: HTTP Error 410: Gone",
+                "VideoUnavailable",
+            ),
+            ("ERROR: [youtube] abc: Video unavailable", "VideoUnavailable"),
+            (
+                "ERROR: [youtube] abc: Unable to download webpage: <urlopen error [Errno -3] Try again>",
+                "NetworkInterrupted",
+            ),
+            (
+                "ERROR: [youtube] abc: Unable to download webpage: <urlopen error [Errno 104] Connection reset by peer>",
+                "NetworkInterrupted",
+            ),
+            (
+                "ERROR: [youtube] abc: The uploader has not made this video available in your country",
+                "GeoBlocked",
+            ),
+            ("ERROR: [youtube] abc: Private video. Sign in if you've been granted access to this video", "PrivateVideo"),
+            (
+                "ERROR: [youtube] abc: Sign in to confirm your age. This video may be inappropriate for some users.",
+                "SignInRequired",
+            ),
+            ("ERROR: unable to write data: [Errno 28] No space left on device", "InsufficientSpace"),
+            ("ERROR: something unexpected", "YtdlpError"),
+        ];
+
+        for (stderr, expected) in cases {
+            let error = YtdlpService::classify_error(stderr);
+            let variant = format!("{:?}", error);
+            assert!(
+                variant.starts_with(expected),
+                "{} 应该归类为 {}，实际为 {}",
+                stderr,
+                expected,
+                variant
+            );
+        }
+    }
+
+    #[test]
+    fn test_unclassified_error_message_has_no_mixed_language() {
+        let error = YtdlpService::classify_error("ERROR: something unexpected\n");
+        assert_eq!(
+            error.localized_message(crate::models::Language::EnUs),
+            "The download tool failed: ERROR: something unexpected"
+        );
+    }
+
+    #[test]
+    fn test_parse_output_path() {
+        let output = [
+            "[youtube] Extracting URL: https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+            "[info] dQw4w9WgXcQ: Downloading 1 format(s): 137+140",
+            "[download] Destination: D:\\Videos\\Rick_Astley.f137.mp4",
+            "[download]  45.2% of 280.00MiB at 2.50MiB/s ETA 00:52",
+            "[download] 100% of 280.00MiB in 01:52",
+            "[download] Destination: D:\\Videos\\Rick_Astley.f140.m4a",
+            "[download] 100% of 3.27MiB in 00:01",
+            "[Merger] Merging formats into \"D:\\Videos\\Rick_Astley.mp4\"",
+            "Deleting original file D:\\Videos\\Rick_Astley.f137.mp4 (pass -k to keep)",
+        ];
+        let file_path = output.iter().rev().find_map(|line| YtdlpService::parse_output_path(line));
+        assert_eq!(file_path.as_deref(), Some("D:\\Videos\\Rick_Astley.mp4"));
+
+        // 单一格式、已下载过和格式转换
+        assert_eq!(
+            YtdlpService::parse_output_path("[download] Destination: /downloads/a b.webm").as_deref(),
+            Some("/downloads/a b.webm")
+        );
+        assert_eq!(
+            YtdlpService::parse_output_path("[download] /downloads/a.mp4 has already been downloaded")
+                .as_deref(),
+            Some("/downloads/a.mp4")
+        );
+        assert_eq!(
+            YtdlpService::parse_output_path("[ExtractAudio] Destination: /downloads/a.mp3").as_deref(),
+            Some("/downloads/a.mp3")
+        );
+        assert_eq!(YtdlpService::parse_output_path("[download] 100% of 3.27MiB in 00:01"), None);
+        assert_eq!(
+            YtdlpService::parse_output_path(
+                "[download] Rick Astley has already been recorded in the archive"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_archive_skip_line() {
+        assert!(YtdlpService::is_archive_skip(
+            "[download] Rick Astley - Never Gonna Give You Up has already been recorded in the archive"
+        ));
+        assert!(!YtdlpService::is_archive_skip("[download]  45.2% of 280.00MiB at 2.50MiB/s ETA 00:52"));
+    }
+
+    #[test]
+    fn test_network_error_keeps_last_error_line() {
+        let stderr = "WARNING: retrying\nERROR: Read timed out.\n";
+        match YtdlpService::classify_error(stderr) {
+            AppError::NetworkInterrupted(detail) => assert_eq!(detail, "ERROR: Read timed out."),
+            other => panic!("意外的错误类型: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_signal_ignores_run() {
+        let (tx, mut rx) = watch::channel(TaskSignal::Run);
+
+        tx.send(TaskSignal::Run).unwrap();
+        tx.send(TaskSignal::Restart).unwrap();
+        assert_eq!(YtdlpService::wait_for_signal(&mut rx).await, TaskSignal::Restart);
+
+        // 同一信号再次发送时仍然会被接收（如多次调整限速）
+        tx.send(TaskSignal::Restart).unwrap();
+        assert_eq!(YtdlpService::wait_for_signal(&mut rx).await, TaskSignal::Restart);
+
+        tx.send(TaskSignal::Cancel).unwrap();
+        assert_eq!(YtdlpService::wait_for_signal(&mut rx).await, TaskSignal::Cancel);
+    }
+
+    #[test]
+    fn test_options_from_config() {
+        let mut config = AppConfig::default();
+        assert!(YtdlpOptions::from_config(&config).proxy.is_none());
+
+        config.proxy = Some(crate::models::ProxyConfig {
+            scheme: crate::models::ProxyScheme::Http,
+            host: "127.0.0.1".to_string(),
+            port: 7890,
+            username: None,
+            password: None,
+        });
+        assert_eq!(
+            YtdlpOptions::from_config(&config).proxy,
+            Some("http://127.0.0.1:7890".to_string())
+        );
+
+        // 存档路径为空时不使用存档
+        config.download_archive = Some(" ".to_string());
+        assert!(YtdlpOptions::from_config(&config).download_archive.is_none());
+        config.download_archive = Some("/scripts/archive.txt".to_string());
+        let args = YtdlpOptions::from_config(&config).to_download_args();
+        assert!(args.windows(2).any(|w| w == ["--download-archive", "/scripts/archive.txt"]));
+    }
+
     // 测试进度解析
     #[test]
     fn test_parse_progress_valid() {
@@ -1128,6 +1887,9 @@ mod tests {
             let service = YtdlpService {
                 ytdlp_path: "yt-dlp".to_string(),
                 active_downloads: Arc::new(Mutex::new(std::collections::HashMap::new())),
+                options: YtdlpOptions::default(),
+                output: None,
+                info_cache: None,
             };
 
             // 定义常见的分辨率选项
@@ -1203,6 +1965,9 @@ mod tests {
             let service = YtdlpService {
                 ytdlp_path: "yt-dlp".to_string(),
                 active_downloads: Arc::new(Mutex::new(std::collections::HashMap::new())),
+                options: YtdlpOptions::default(),
+                output: None,
+                info_cache: None,
             };
 
             // 定义可用的分辨率选项（不包含 1080p）
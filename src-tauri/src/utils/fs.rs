@@ -1,11 +1,12 @@
 use crate::error::AppError;
+use crate::utils::i18n::{tr, Text};
 use std::path::{Path, PathBuf};
 use std::fs;
 
 /// 验证路径是否具有写入权限
 pub fn verify_path_permission(path: &str) -> Result<bool, AppError> {
     let path = Path::new(path);
-    
+
     // 如果路径不存在，检查父目录
     let check_path = if path.exists() {
         path
@@ -13,11 +14,11 @@ pub fn verify_path_permission(path: &str) -> Result<bool, AppError> {
         path.parent().ok_or_else(|| {
             AppError::FileSystemError(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
-                "无效的路径",
+                tr(Text::InvalidPath, &[]),
             ))
         })?
     };
-    
+
     // 尝试创建临时文件来测试写入权限
     let test_file = check_path.join(".write_test_tmp");
     match fs::write(&test_file, b"test") {
@@ -35,7 +36,7 @@ pub fn verify_path_permission(path: &str) -> Result<bool, AppError> {
 pub fn resolve_filename_conflict(dir: &Path, filename: &str) -> Result<String, AppError> {
     let mut final_name = filename.to_string();
     let mut counter = 1;
-    
+
     // 分离文件名和扩展名
     let path = Path::new(filename);
     let stem = path.file_stem()
@@ -44,7 +45,7 @@ pub fn resolve_filename_conflict(dir: &Path, filename: &str) -> Result<String, A
     let extension = path.extension()
         .and_then(|s| s.to_str())
         .unwrap_or("");
-    
+
     // 检查文件是否存在，如果存在则添加数字后缀
     while dir.join(&final_name).exists() {
         if extension.is_empty() {
@@ -61,14 +62,14 @@ pub fn resolve_filename_conflict(dir: &Path, filename: &str) -> Result<String, A
             )));
         }
     }
-    
+
     Ok(final_name)
 }
 
 /// 跨平台路径规范化
 pub fn normalize_path(path: &str) -> Result<PathBuf, AppError> {
     let path = Path::new(path);
-    
+
     // 展开相对路径和符号链接
     match path.canonicalize() {
         Ok(canonical) => Ok(canonical),
@@ -96,14 +97,14 @@ pub fn normalize_path(path: &str) -> Result<PathBuf, AppError> {
 /// 打开文件或文件夹（使用系统默认程序）
 pub fn open_path(path: &str) -> Result<(), AppError> {
     let path = Path::new(path);
-    
+
     if !path.exists() {
         return Err(AppError::FileSystemError(std::io::Error::new(
             std::io::ErrorKind::NotFound,
-            "文件或文件夹不存在",
+            tr(Text::PathNotFound, &[]),
         )));
     }
-    
+
     #[cfg(target_os = "windows")]
     {
         std::process::Command::new("explorer")
@@ -111,7 +112,7 @@ pub fn open_path(path: &str) -> Result<(), AppError> {
             .spawn()
             .map_err(AppError::FileSystemError)?;
     }
-    
+
     #[cfg(target_os = "macos")]
     {
         std::process::Command::new("open")
@@ -119,7 +120,7 @@ pub fn open_path(path: &str) -> Result<(), AppError> {
             .spawn()
             .map_err(AppError::FileSystemError)?;
     }
-    
+
     #[cfg(target_os = "linux")]
     {
         std::process::Command::new("xdg-open")
@@ -127,16 +128,335 @@ pub fn open_path(path: &str) -> Result<(), AppError> {
             .spawn()
             .map_err(AppError::FileSystemError)?;
     }
-    
+
     Ok(())
 }
 
+/// 确保下载目录存在且可写
+///
+/// 目录不存在时自动创建；所在的可移动磁盘未连接时返回 `DriveUnavailable`，
+/// 无法创建或写入时返回 `PermissionDenied`
+pub fn ensure_writable_dir(path: &Path) -> Result<(), AppError> {
+    if let Some(root) = volume_root(path) {
+        if !root.exists() {
+            return Err(AppError::DriveUnavailable(root.to_string_lossy().to_string()));
+        }
+    }
+
+    let display = path.to_string_lossy().to_string();
+    if !path.exists() {
+        fs::create_dir_all(path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::PermissionDenied => AppError::PermissionDenied(display.clone()),
+            _ => AppError::FileSystemError(e),
+        })?;
+    } else if !path.is_dir() {
+        return Err(AppError::FileSystemError(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            tr(Text::NotAFolder, &[&display]),
+        )));
+    }
+
+    if !verify_path_permission(&display)? {
+        return Err(AppError::PermissionDenied(display));
+    }
+    Ok(())
+}
+
+/// 获取路径所在的卷（盘符或可移动磁盘的挂载点）
+///
+/// - Windows: `E:\`
+/// - macOS: `/Volumes/<name>`
+/// - Linux: `/media/<user>/<name>`、`/run/media/<user>/<name>`、`/mnt/<name>`
+///
+/// 系统盘上的普通路径返回 `None`
+fn volume_root(path: &Path) -> Option<PathBuf> {
+    use std::path::Component;
+
+    let mut components = path.components();
+    if let Some(Component::Prefix(prefix)) = components.next() {
+        let mut root = PathBuf::from(prefix.as_os_str());
+        root.push(std::path::MAIN_SEPARATOR_STR);
+        return Some(root);
+    }
+
+    const MOUNT_BASES: [(&str, usize); 4] =
+        [("/Volumes", 1), ("/mnt", 1), ("/media", 2), ("/run/media", 2)];
+
+    MOUNT_BASES.iter().find_map(|(base, depth)| {
+        let rest = path.strip_prefix(base).ok()?;
+        let parts: Vec<_> = rest.components().take(*depth).collect();
+        if parts.len() < *depth {
+            return None;
+        }
+        let mut root = PathBuf::from(base);
+        root.extend(parts);
+        Some(root)
+    })
+}
+
+/// 获取路径所在卷的可用空间（字节）
+///
+/// 路径不存在时使用最近的已存在的上级目录
+pub fn available_space(path: &Path) -> Result<u64, AppError> {
+    let existing = path
+        .ancestors()
+        .find(|p| p.exists())
+        .ok_or_else(|| {
+            AppError::FileSystemError(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                tr(Text::InvalidPath, &[]),
+            ))
+        })?;
+
+    Ok(fs2::available_space(existing)?)
+}
+
+/// 检查路径所在卷是否有足够的空间
+///
+/// 可用空间需要大于 `required + margin`，否则返回 `InsufficientSpace`
+pub fn ensure_free_space(path: &Path, required: u64, margin: u64) -> Result<(), AppError> {
+    let available = available_space(path)?;
+    if available < required.saturating_add(margin) {
+        return Err(AppError::InsufficientSpace);
+    }
+    Ok(())
+}
+
+/// 格式化文件大小（如 `1.50 GB`）
+pub fn format_size(size: u64) -> String {
+    if size >= 1_073_741_824 {
+        // >= 1 GB
+        format!("{:.2} GB", size as f64 / 1_073_741_824.0)
+    } else if size >= 1_048_576 {
+        // >= 1 MB
+        format!("{:.2} MB", size as f64 / 1_048_576.0)
+    } else if size >= 1024 {
+        // >= 1 KB
+        format!("{:.2} KB", size as f64 / 1024.0)
+    } else {
+        format!("{} B", size)
+    }
+}
+
+/// 递归列出目录下的所有文件及其大小
+///
+/// 不跟随符号链接，无法读取的子目录会被跳过
+pub fn list_files(root: &Path) -> Vec<(PathBuf, u64)> {
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                dirs.push(entry.path());
+            } else if file_type.is_file() {
+                if let Ok(metadata) = entry.metadata() {
+                    files.push((entry.path(), metadata.len()));
+                }
+            }
+        }
+    }
+    files
+}
+
+/// yt-dlp 写在媒体文件旁的附属文件（`<文件名>.<后缀>`）
+const SIDECAR_SUFFIXES: [&str; 6] = [
+    "info.json",
+    "description",
+    "annotations.xml",
+    "live_chat.json",
+    "part",
+    "ytdl",
+];
+
+/// 缩略图的扩展名
+const THUMBNAIL_EXTENSIONS: [&str; 4] = ["jpg", "jpeg", "png", "webp"];
+
+/// 字幕的扩展名（字幕可带语言代码，如 `.en.vtt`）
+const SUBTITLE_EXTENSIONS: [&str; 5] = ["vtt", "srt", "ass", "lrc", "ttml"];
+
+/// 列出媒体文件旁的附属文件（缩略图、字幕、信息 JSON、未完成的分片等）
+pub fn sidecar_files(path: &Path) -> Vec<PathBuf> {
+    let (Some(dir), Some(media_name)) = (path.parent(), path.file_name().and_then(|n| n.to_str()))
+    else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            name.to_str().is_some_and(|name| is_sidecar_of(media_name, name))
+                && entry.path().is_file()
+        })
+        .map(|entry| entry.path())
+        .collect();
+    files.sort();
+    files
+}
+
+/// 是否为缩略图文件
+pub fn is_thumbnail(path: &Path) -> bool {
+    has_extension(path, &THUMBNAIL_EXTENSIONS)
+}
+
+/// 是否为字幕文件
+pub fn is_subtitle(path: &Path) -> bool {
+    has_extension(path, &SUBTITLE_EXTENSIONS)
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.contains(&ext.to_lowercase().as_str()))
+}
+
+/// 删除媒体文件及其附属文件
+///
+/// 媒体文件不存在时只删除附属文件，返回实际删除的文件
+pub fn remove_media_files(path: &Path) -> Result<Vec<PathBuf>, AppError> {
+    let mut removed = Vec::new();
+    match fs::remove_file(path) {
+        Ok(()) => removed.push(path.to_path_buf()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+
+    for sidecar in sidecar_files(path) {
+        if fs::remove_file(&sidecar).is_ok() {
+            removed.push(sidecar);
+        }
+    }
+    Ok(removed)
+}
+
+/// 判断文件是否为媒体文件的附属文件
+fn is_sidecar_of(media_name: &str, name: &str) -> bool {
+    let (stem, media_ext) = media_name.rsplit_once('.').unwrap_or((media_name, ""));
+    let Some(suffix) = name.strip_prefix(stem).and_then(|rest| rest.strip_prefix('.')) else {
+        return false;
+    };
+    if name == media_name {
+        return false;
+    }
+    if SIDECAR_SUFFIXES.contains(&suffix)
+        || suffix == format!("{}.part", media_ext)
+        || suffix == format!("{}.ytdl", media_ext)
+    {
+        return true;
+    }
+
+    match suffix.split_once('.') {
+        None => THUMBNAIL_EXTENSIONS.contains(&suffix) || SUBTITLE_EXTENSIONS.contains(&suffix),
+        // 合并前的单独格式文件，如 `.f137.mp4`、`.f251.webm.part`
+        Some((format, _)) if is_format_tag(format) => true,
+        Some((lang, ext)) => {
+            is_language_tag(lang)
+                && (THUMBNAIL_EXTENSIONS.contains(&ext) || SUBTITLE_EXTENSIONS.contains(&ext))
+        }
+    }
+}
+
+/// yt-dlp 的格式标记（`f` 加数字）
+fn is_format_tag(text: &str) -> bool {
+    text.strip_prefix('f')
+        .is_some_and(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// 字幕的语言代码，如 `en`、`zh-Hans`、`pt-BR`
+fn is_language_tag(text: &str) -> bool {
+    let mut parts = text.split('-');
+    let primary = parts.next().unwrap_or_default();
+    (2..=3).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_lowercase())
+        && parts.all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs;
     use tempfile::TempDir;
-    
+
+    #[test]
+    fn test_is_sidecar_of() {
+        let media = "My_Video.mp4";
+        for name in [
+            "My_Video.info.json",
+            "My_Video.description",
+            "My_Video.webp",
+            "My_Video.en.vtt",
+            "My_Video.zh-Hans.srt",
+            "My_Video.mp4.part",
+            "My_Video.f137.mp4",
+            "My_Video.f251.webm.part",
+        ] {
+            assert!(is_sidecar_of(media, name), "{}", name);
+        }
+        for name in [
+            "My_Video.mp4",
+            "My_Video.Part2.mp4",
+            "My_Video.Part2.jpg",
+            "My_Video_2.webp",
+            "My_Video.mkv",
+            "Other.info.json",
+        ] {
+            assert!(!is_sidecar_of(media, name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_list_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested = temp_dir.path().join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(temp_dir.path().join("top.mp4"), b"12").unwrap();
+        fs::write(nested.join("deep.mp4"), b"1234").unwrap();
+
+        let mut files = list_files(temp_dir.path());
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                (nested.join("deep.mp4"), 4),
+                (temp_dir.path().join("top.mp4"), 2),
+            ]
+        );
+        assert!(list_files(&temp_dir.path().join("missing")).is_empty());
+    }
+
+    #[test]
+    fn test_remove_media_files() {
+        let temp_dir = TempDir::new().unwrap();
+        for name in ["video.mp4", "video.jpg", "video.en.vtt", "video.Part2.mp4", "other.jpg"] {
+            fs::write(temp_dir.path().join(name), b"x").unwrap();
+        }
+        
+        let sidecars = sidecar_files(&temp_dir.path().join("video.mp4"));
+        assert_eq!(
+            sidecars,
+            vec![temp_dir.path().join("video.en.vtt"), temp_dir.path().join("video.jpg")]
+        );
+        assert!(is_subtitle(&sidecars[0]));
+        assert!(is_thumbnail(&sidecars[1]));
+
+        let removed = remove_media_files(&temp_dir.path().join("video.mp4")).unwrap();
+        assert_eq!(removed.len(), 3);
+        assert!(!temp_dir.path().join("video.en.vtt").exists());
+        assert!(temp_dir.path().join("video.Part2.mp4").exists());
+        assert!(temp_dir.path().join("other.jpg").exists());
+        
+        // 媒体文件已不存在时不报错
+        assert!(remove_media_files(&temp_dir.path().join("video.mp4")).unwrap().is_empty());
+    }
+
     #[test]
     fn test_verify_path_permission_writable() {
         let temp_dir = TempDir::new().unwrap();
@@ -146,7 +466,7 @@ mod tests {
         assert!(result.is_ok());
         assert!(result.unwrap());
     }
-    
+
     #[test]
     fn test_resolve_filename_conflict_no_conflict() {
         let temp_dir = TempDir::new().unwrap();
@@ -156,7 +476,7 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), "test.txt");
     }
-    
+
     #[test]
     fn test_resolve_filename_conflict_with_conflict() {
         let temp_dir = TempDir::new().unwrap();
@@ -169,7 +489,7 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), "test_1.txt");
     }
-    
+
     #[test]
     fn test_resolve_filename_conflict_multiple_conflicts() {
         let temp_dir = TempDir::new().unwrap();
@@ -184,7 +504,7 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), "test_3.txt");
     }
-    
+
     #[test]
     fn test_resolve_filename_conflict_no_extension() {
         let temp_dir = TempDir::new().unwrap();
@@ -197,7 +517,7 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), "test_1");
     }
-    
+
     #[test]
     fn test_normalize_path_existing() {
         let temp_dir = TempDir::new().unwrap();
@@ -206,7 +526,7 @@ mod tests {
         let result = normalize_path(path);
         assert!(result.is_ok());
     }
-    
+
     // Feature: youtube-downloader-tool, Property 12: 路径权限验证正确性
     // Validates: Requirements 4.3
     #[cfg(test)]
@@ -614,4 +934,81 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_available_space_uses_existing_ancestor() {
+        let temp_dir = TempDir::new().unwrap();
+        let missing = temp_dir.path().join("not").join("yet").join("created");
+
+        let space = available_space(&missing).unwrap();
+        assert_eq!(space, available_space(temp_dir.path()).unwrap());
+        assert!(space > 0);
+    }
+
+    #[test]
+    fn test_ensure_free_space() {
+        let temp_dir = TempDir::new().unwrap();
+
+        assert!(ensure_free_space(temp_dir.path(), 0, 0).is_ok());
+        assert!(matches!(
+            ensure_free_space(temp_dir.path(), u64::MAX / 2, u64::MAX / 2),
+            Err(AppError::InsufficientSpace)
+        ));
+    }
+
+    #[test]
+    fn test_ensure_writable_dir_creates_missing_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let target = temp_dir.path().join("videos").join("2024");
+
+        ensure_writable_dir(&target).unwrap();
+        assert!(target.is_dir());
+
+        // 已存在的目录再次检查也应成功
+        ensure_writable_dir(&target).unwrap();
+    }
+
+    #[test]
+    fn test_ensure_writable_dir_rejects_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("video.mp4");
+        fs::write(&file, b"data").unwrap();
+
+        assert!(ensure_writable_dir(&file).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_volume_root() {
+        assert_eq!(
+            volume_root(Path::new("/Volumes/USB/Videos")),
+            Some(PathBuf::from("/Volumes/USB"))
+        );
+        assert_eq!(
+            volume_root(Path::new("/run/media/alice/USB/Videos")),
+            Some(PathBuf::from("/run/media/alice/USB"))
+        );
+        assert_eq!(
+            volume_root(Path::new("/media/alice/USB")),
+            Some(PathBuf::from("/media/alice/USB"))
+        );
+        assert_eq!(volume_root(Path::new("/media/alice")), None);
+        assert_eq!(volume_root(Path::new("/home/alice/Videos")), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ensure_writable_dir_missing_drive() {
+        let result = ensure_writable_dir(Path::new("/Volumes/__missing_test_drive__/Videos"));
+        assert!(matches!(result, Err(AppError::DriveUnavailable(_))));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_volume_root_windows() {
+        assert_eq!(
+            volume_root(Path::new(r"E:\Videos")),
+            Some(PathBuf::from(r"E:\"))
+        );
+    }
 }
@@ -0,0 +1,360 @@
+// 后端用户可见文本的多语言目录
+// 模板中的 {0}、{1} 等占位符按顺序替换为参数
+
+use crate::models::Language;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// 当前界面语言
+static CURRENT_LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+/// 设置当前界面语言
+pub fn set_language(language: Language) {
+    CURRENT_LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+/// 获取当前界面语言
+pub fn current_language() -> Language {
+    match CURRENT_LANGUAGE.load(Ordering::Relaxed) {
+        x if x == Language::EnUs as u8 => Language::EnUs,
+        _ => Language::ZhCn,
+    }
+}
+
+/// 文本键
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Text {
+    YtdlpFailed,
+    ParseFailed,
+    FileSystemFailed,
+    NetworkFailed,
+    ConfigInvalid,
+    InvalidUrl,
+    UnsupportedSite,
+    TaskNotFound,
+    PermissionDenied,
+    InsufficientSpace,
+    YtdlpNotFound,
+    DataFormatInvalid,
+    HttpForbidden,
+    RateLimited,
+    NetworkInterrupted,
+    GeoBlocked,
+    PrivateVideo,
+    SignInRequired,
+    DriveUnavailable,
+    SelectFolderTitle,
+    UnknownSize,
+    InvalidRateLimit,
+    InvalidTime,
+    ExportLogsTitle,
+    PlaylistNotSupported,
+    ImportArchiveTitle,
+    ExportArchiveTitle,
+    DatabaseFailed,
+    HistoryItemNotFound,
+    HistoryReportTitle,
+    HistoryReportSummary,
+    ReportColumnVideo,
+    ReportColumnUploader,
+    ReportColumnResolution,
+    ReportColumnSize,
+    ReportColumnDuration,
+    ReportColumnDownloadedAt,
+    ReportColumnFile,
+    VideoUnavailable,
+    ProcessFailed,
+    ProcessOutputUnavailable,
+    VersionCheckFailed,
+    TaskAlreadyRunning,
+    NoUpdateSource,
+    UnsupportedPlatform,
+    PlatformAssetNotFound,
+    DataDirUnavailable,
+    ConfigDirUnavailable,
+    ConfigSaveFailed,
+    LoggerInitFailed,
+    InvalidProxy,
+    InvalidPath,
+    PathNotFound,
+    NotAFolder,
+    HistoryItemsMissing,
+    HistoryFormatUnknown,
+    InfoJsonInvalid,
+    VideoIdMissing,
+    VideoTitleMissing,
+    NoVideoFormats,
+    FormatListMissing,
+}
+
+impl Text {
+    /// 所有文本键
+    #[cfg(test)]
+    const ALL: [Text; 61] = [
+        Text::YtdlpFailed,
+        Text::ParseFailed,
+        Text::FileSystemFailed,
+        Text::NetworkFailed,
+        Text::ConfigInvalid,
+        Text::InvalidUrl,
+        Text::UnsupportedSite,
+        Text::TaskNotFound,
+        Text::PermissionDenied,
+        Text::InsufficientSpace,
+        Text::YtdlpNotFound,
+        Text::DataFormatInvalid,
+        Text::HttpForbidden,
+        Text::RateLimited,
+        Text::NetworkInterrupted,
+        Text::GeoBlocked,
+        Text::PrivateVideo,
+        Text::SignInRequired,
+        Text::DriveUnavailable,
+        Text::SelectFolderTitle,
+        Text::UnknownSize,
+        Text::InvalidRateLimit,
+        Text::InvalidTime,
+        Text::ExportLogsTitle,
+        Text::PlaylistNotSupported,
+        Text::ImportArchiveTitle,
+        Text::ExportArchiveTitle,
+        Text::DatabaseFailed,
+        Text::HistoryItemNotFound,
+        Text::HistoryReportTitle,
+        Text::HistoryReportSummary,
+        Text::ReportColumnVideo,
+        Text::ReportColumnUploader,
+        Text::ReportColumnResolution,
+        Text::ReportColumnSize,
+        Text::ReportColumnDuration,
+        Text::ReportColumnDownloadedAt,
+        Text::ReportColumnFile,
+        Text::VideoUnavailable,
+        Text::ProcessFailed,
+        Text::ProcessOutputUnavailable,
+        Text::VersionCheckFailed,
+        Text::TaskAlreadyRunning,
+        Text::NoUpdateSource,
+        Text::UnsupportedPlatform,
+        Text::PlatformAssetNotFound,
+        Text::DataDirUnavailable,
+        Text::ConfigDirUnavailable,
+        Text::ConfigSaveFailed,
+        Text::LoggerInitFailed,
+        Text::InvalidProxy,
+        Text::InvalidPath,
+        Text::PathNotFound,
+        Text::NotAFolder,
+        Text::HistoryItemsMissing,
+        Text::HistoryFormatUnknown,
+        Text::InfoJsonInvalid,
+        Text::VideoIdMissing,
+        Text::VideoTitleMissing,
+        Text::NoVideoFormats,
+        Text::FormatListMissing,
+    ];
+
+    /// 获取指定语言的文本模板
+    pub fn template(self, language: Language) -> &'static str {
+        match language {
+            Language::ZhCn => self.zh_cn(),
+            Language::EnUs => self.en_us(),
+        }
+    }
+
+    fn zh_cn(self) -> &'static str {
+        match self {
+            Text::YtdlpFailed => "下载工具执行失败：{0}",
+            Text::ParseFailed => "视频信息解析失败，请检查链接是否正确",
+            Text::FileSystemFailed => "文件操作失败：{0}",
+            Text::NetworkFailed => "网络连接失败，请检查网络设置",
+            Text::ConfigInvalid => "配置错误：{0}",
+            Text::InvalidUrl => "无效的视频链接，请输入正确的 URL",
+            Text::UnsupportedSite => "该网站暂不支持，yt-dlp 支持 1000+ 网站，请尝试其他链接",
+            Text::TaskNotFound => "下载任务不存在",
+            Text::PermissionDenied => "没有写入权限，请选择其他保存位置",
+            Text::InsufficientSpace => "磁盘空间不足，请清理磁盘后重试",
+            Text::YtdlpNotFound => "yt-dlp 未找到，请尝试更新或重新安装",
+            Text::DataFormatInvalid => "数据格式错误：{0}",
+            Text::HttpForbidden => "服务器拒绝访问 (403)，请稍后重试或更新 yt-dlp",
+            Text::RateLimited => "请求过于频繁 (429)，请稍后重试",
+            Text::NetworkInterrupted => "网络连接中断，请检查网络设置",
+            Text::GeoBlocked => "该视频在当前地区不可用，请尝试使用代理",
+            Text::PrivateVideo => "这是私享视频，无法下载",
+            Text::SignInRequired => "该视频需要登录才能观看，请在设置中配置浏览器 Cookie",
+            Text::DriveUnavailable => "保存位置所在的磁盘 {0} 未连接，请插入设备或选择其他保存位置",
+            Text::SelectFolderTitle => "选择保存文件夹",
+            Text::UnknownSize => "未知大小",
+            Text::InvalidRateLimit => "无效的限速值: {0}",
+            Text::InvalidTime => "无效的时间: {0}",
+            Text::ExportLogsTitle => "导出日志",
+            Text::PlaylistNotSupported => "这是播放列表或频道链接，请输入单个视频的链接",
+            Text::ImportArchiveTitle => "导入下载存档",
+            Text::ExportArchiveTitle => "导出下载存档",
+            Text::DatabaseFailed => "历史记录数据库错误：{0}",
+            Text::HistoryItemNotFound => "历史记录不存在",
+            Text::HistoryReportTitle => "下载历史报告",
+            Text::HistoryReportSummary => "{0} 个视频，共 {1}",
+            Text::ReportColumnVideo => "视频",
+            Text::ReportColumnUploader => "上传者",
+            Text::ReportColumnResolution => "分辨率",
+            Text::ReportColumnSize => "大小",
+            Text::ReportColumnDuration => "时长",
+            Text::ReportColumnDownloadedAt => "下载时间",
+            Text::ReportColumnFile => "文件",
+            Text::VideoUnavailable => "视频不存在或已被删除",
+            Text::ProcessFailed => "无法运行下载工具：{0}",
+            Text::ProcessOutputUnavailable => "无法读取下载工具的输出",
+            Text::VersionCheckFailed => "获取 yt-dlp 版本失败",
+            Text::TaskAlreadyRunning => "该任务已在下载中",
+            Text::NoUpdateSource => "未配置更新源",
+            Text::UnsupportedPlatform => "不支持当前操作系统",
+            Text::PlatformAssetNotFound => "更新中没有 {0} 的下载文件",
+            Text::DataDirUnavailable => "无法获取数据目录",
+            Text::ConfigDirUnavailable => "无法获取配置目录",
+            Text::ConfigSaveFailed => "无法保存配置文件：{0}",
+            Text::LoggerInitFailed => "日志初始化失败：{0}",
+            Text::InvalidProxy => "代理地址无效：{0}",
+            Text::InvalidPath => "无效的路径",
+            Text::PathNotFound => "文件或文件夹不存在",
+            Text::NotAFolder => "保存路径不是文件夹：{0}",
+            Text::HistoryItemsMissing => "缺少 items 数组",
+            Text::HistoryFormatUnknown => "历史记录应为数组或对象",
+            Text::InfoJsonInvalid => "JSON 解析失败: {0}",
+            Text::VideoIdMissing => "缺少视频 ID",
+            Text::VideoTitleMissing => "缺少视频标题",
+            Text::NoVideoFormats => "没有可用的视频格式",
+            Text::FormatListMissing => "缺少格式列表",
+        }
+    }
+
+    fn en_us(self) -> &'static str {
+        match self {
+            Text::YtdlpFailed => "The download tool failed: {0}",
+            Text::ParseFailed => "Failed to read the video information. Please check the link",
+            Text::FileSystemFailed => "File operation failed: {0}",
+            Text::NetworkFailed => "Network connection failed. Please check your network settings",
+            Text::ConfigInvalid => "Configuration error: {0}",
+            Text::InvalidUrl => "Invalid video link. Please enter a valid URL",
+            Text::UnsupportedSite => {
+                "This site is not supported yet. yt-dlp supports 1000+ sites, please try another link"
+            }
+            Text::TaskNotFound => "The download task does not exist",
+            Text::PermissionDenied => "No write permission. Please choose another save location",
+            Text::InsufficientSpace => "Not enough disk space. Please free up some space and try again",
+            Text::YtdlpNotFound => "yt-dlp was not found. Please try updating or reinstalling it",
+            Text::DataFormatInvalid => "Invalid data format: {0}",
+            Text::HttpForbidden => {
+                "Access denied by the server (403). Please retry later or update yt-dlp"
+            }
+            Text::RateLimited => "Too many requests (429). Please try again later",
+            Text::NetworkInterrupted => {
+                "The network connection was interrupted. Please check your network settings"
+            }
+            Text::GeoBlocked => "This video is not available in your region. Try using a proxy",
+            Text::PrivateVideo => "This video is private and cannot be downloaded",
+            Text::SignInRequired => {
+                "This video requires signing in. Please configure browser cookies in settings"
+            }
+            Text::DriveUnavailable => {
+                "The drive {0} for the save location is not connected. Please plug it in or choose another location"
+            }
+            Text::SelectFolderTitle => "Select download folder",
+            Text::UnknownSize => "Unknown size",
+            Text::InvalidRateLimit => "Invalid rate limit: {0}",
+            Text::InvalidTime => "Invalid time: {0}",
+            Text::ExportLogsTitle => "Export logs",
+            Text::PlaylistNotSupported => {
+                "This is a playlist or channel link. Please enter a link to a single video"
+            }
+            Text::ImportArchiveTitle => "Import download archive",
+            Text::ExportArchiveTitle => "Export download archive",
+            Text::DatabaseFailed => "History database error: {0}",
+            Text::HistoryItemNotFound => "The history entry does not exist",
+            Text::HistoryReportTitle => "Download history report",
+            Text::HistoryReportSummary => "{0} videos, {1} in total",
+            Text::ReportColumnVideo => "Video",
+            Text::ReportColumnUploader => "Uploader",
+            Text::ReportColumnResolution => "Resolution",
+            Text::ReportColumnSize => "Size",
+            Text::ReportColumnDuration => "Duration",
+            Text::ReportColumnDownloadedAt => "Downloaded",
+            Text::ReportColumnFile => "File",
+            Text::VideoUnavailable => "The video does not exist or has been removed",
+            Text::ProcessFailed => "Could not run the download tool: {0}",
+            Text::ProcessOutputUnavailable => "Could not read the output of the download tool",
+            Text::VersionCheckFailed => "Failed to get the yt-dlp version",
+            Text::TaskAlreadyRunning => "This task is already downloading",
+            Text::NoUpdateSource => "No update source is configured",
+            Text::UnsupportedPlatform => "This operating system is not supported",
+            Text::PlatformAssetNotFound => "The update has no download file for {0}",
+            Text::DataDirUnavailable => "Could not locate the data folder",
+            Text::ConfigDirUnavailable => "Could not locate the configuration folder",
+            Text::ConfigSaveFailed => "Could not save the configuration file: {0}",
+            Text::LoggerInitFailed => "Failed to initialize logging: {0}",
+            Text::InvalidProxy => "Invalid proxy address: {0}",
+            Text::InvalidPath => "Invalid path",
+            Text::PathNotFound => "The file or folder does not exist",
+            Text::NotAFolder => "The save location is not a folder: {0}",
+            Text::HistoryItemsMissing => "The items list is missing",
+            Text::HistoryFormatUnknown => "History data must be a list or an object",
+            Text::InfoJsonInvalid => "Failed to parse JSON: {0}",
+            Text::VideoIdMissing => "Video ID is missing",
+            Text::VideoTitleMissing => "Video title is missing",
+            Text::NoVideoFormats => "No video formats are available",
+            Text::FormatListMissing => "Format list is missing",
+        }
+    }
+}
+
+/// 按指定语言生成文本
+pub fn render(text: Text, language: Language, args: &[&dyn Display]) -> String {
+    let mut result = text.template(language).to_string();
+    for (i, arg) in args.iter().enumerate() {
+        result = result.replace(&format!("{{{}}}", i), &arg.to_string());
+    }
+    result
+}
+
+/// 按当前语言生成文本
+pub fn tr(text: Text, args: &[&dyn Display]) -> String {
+    render(text, current_language(), args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 统计模板中的占位符数量
+    fn placeholder_count(template: &str) -> usize {
+        (0..10).filter(|i| template.contains(&format!("{{{}}}", i))).count()
+    }
+
+    #[test]
+    fn test_catalog_is_complete() {
+        for text in Text::ALL {
+            let zh = text.template(Language::ZhCn);
+            let en = text.template(Language::EnUs);
+            assert!(!zh.is_empty() && !en.is_empty(), "{:?} 缺少文本", text);
+            assert_eq!(
+                placeholder_count(zh),
+                placeholder_count(en),
+                "{:?} 各语言的占位符数量应一致",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn test_render_replaces_placeholders() {
+        assert_eq!(
+            render(Text::InvalidRateLimit, Language::EnUs, &[&"fast"]),
+            "Invalid rate limit: fast"
+        );
+        assert_eq!(
+            render(Text::InvalidRateLimit, Language::ZhCn, &[&"fast"]),
+            "无效的限速值: fast"
+        );
+        assert_eq!(render(Text::UnknownSize, Language::EnUs, &[]), "Unknown size");
+    }
+}
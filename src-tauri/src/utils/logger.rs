@@ -0,0 +1,299 @@
+// 日志子系统
+// 日志写入应用数据目录下的 logs/app.log，超过大小上限时轮转为 app.1.log、app.2.log ...
+// 与下载任务相关的日志通过 `task_id` 键值标记，例如 `log::info!(task_id = id; "...")`
+
+use crate::error::AppError;
+use crate::utils::i18n::{tr, Text};
+use log::kv::Key;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 当前日志文件名
+const LOG_FILE_NAME: &str = "app.log";
+
+/// 单个日志文件大小上限（字节）
+const MAX_LOG_SIZE: u64 = 5 * 1024 * 1024;
+
+/// 保留的日志文件数量（包括当前文件）
+const MAX_LOG_FILES: usize = 5;
+
+/// 本程序的日志级别
+const APP_LEVEL: LevelFilter = LevelFilter::Debug;
+
+/// 本程序的模块前缀
+const APP_TARGET: &str = "youtube_downloader_tool";
+
+/// 写入轮转日志文件的日志器
+struct FileLogger {
+    dir: PathBuf,
+    max_size: u64,
+    file: Mutex<Option<LogFile>>,
+}
+
+/// 当前打开的日志文件
+struct LogFile {
+    file: File,
+    size: u64,
+}
+
+impl FileLogger {
+    fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            max_size: MAX_LOG_SIZE,
+            file: Mutex::new(None),
+        }
+    }
+
+    /// 写入一行日志，必要时先轮转
+    fn write_line(&self, line: &str) -> std::io::Result<()> {
+        let mut guard = self.file.lock().unwrap_or_else(|e| e.into_inner());
+
+        if guard.as_ref().is_some_and(|f| f.size >= self.max_size) {
+            *guard = None;
+            rotate(&self.dir)?;
+        }
+
+        if guard.is_none() {
+            let path = self.dir.join(LOG_FILE_NAME);
+            let file = OpenOptions::new().create(true).append(true).open(&path)?;
+            let size = file.metadata()?.len();
+            *guard = Some(LogFile { file, size });
+        }
+
+        let log_file = guard.as_mut().expect("日志文件已打开");
+        log_file.file.write_all(line.as_bytes())?;
+        log_file.size += line.len() as u64;
+        Ok(())
+    }
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        // 依赖库只记录 info 及以上级别，避免刷屏
+        if metadata.target().starts_with(APP_TARGET) {
+            metadata.level() <= APP_LEVEL
+        } else {
+            metadata.level() <= Level::Info
+        }
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let line = format_record(record);
+        if cfg!(debug_assertions) {
+            eprint!("{}", line);
+        }
+        if let Err(e) = self.write_line(&line) {
+            eprintln!("写入日志失败: {}", e);
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(log_file) = self.file.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+            let _ = log_file.file.flush();
+        }
+    }
+}
+
+/// 初始化全局日志器
+pub fn init(dir: &Path) -> Result<(), AppError> {
+    fs::create_dir_all(dir)?;
+    log::set_boxed_logger(Box::new(FileLogger::new(dir.to_path_buf())))
+        .map_err(|e| AppError::ConfigError(tr(Text::LoggerInitFailed, &[&e])))?;
+    log::set_max_level(APP_LEVEL);
+    Ok(())
+}
+
+/// 格式化日志行：时间 级别 [模块] [task:任务ID] 消息
+fn format_record(record: &Record) -> String {
+    let target = record.target();
+    let target = target
+        .strip_prefix(APP_TARGET)
+        .map(|t| t.trim_start_matches("::"))
+        .filter(|t| !t.is_empty())
+        .unwrap_or(target);
+
+    let task = record
+        .key_values()
+        .get(Key::from_str("task_id"))
+        .map(|id| format!("[task:{}] ", id))
+        .unwrap_or_default();
+
+    format!(
+        "{} {:<5} [{}] {}{}\n",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+        record.level(),
+        target,
+        task,
+        record.args()
+    )
+}
+
+/// 第 n 个日志文件的路径（0 为当前文件）
+fn log_file_path(dir: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        dir.join(LOG_FILE_NAME)
+    } else {
+        dir.join(format!("app.{}.log", index))
+    }
+}
+
+/// 轮转日志文件：app.log -> app.1.log -> app.2.log ...，超出保留数量的文件被删除
+fn rotate(dir: &Path) -> std::io::Result<()> {
+    let oldest = log_file_path(dir, MAX_LOG_FILES - 1);
+    if oldest.exists() {
+        fs::remove_file(&oldest)?;
+    }
+    for index in (0..MAX_LOG_FILES - 1).rev() {
+        let from = log_file_path(dir, index);
+        if from.exists() {
+            fs::rename(&from, log_file_path(dir, index + 1))?;
+        }
+    }
+    Ok(())
+}
+
+/// 按时间从旧到新列出现有的日志文件
+fn log_files(dir: &Path) -> Vec<PathBuf> {
+    (0..MAX_LOG_FILES)
+        .rev()
+        .map(|index| log_file_path(dir, index))
+        .filter(|path| path.exists())
+        .collect()
+}
+
+/// 读取最近的日志行（从旧到新），可按任务 ID 过滤
+pub fn read_recent(dir: &Path, task_id: Option<&str>, limit: usize) -> Result<Vec<String>, AppError> {
+    let tag = task_id.map(|id| format!("[task:{}]", id));
+    let mut lines = VecDeque::with_capacity(limit.min(4096));
+
+    for path in log_files(dir) {
+        let content = fs::read(&path)?;
+        for line in String::from_utf8_lossy(&content).lines() {
+            if tag.as_ref().is_some_and(|tag| !line.contains(tag.as_str())) {
+                continue;
+            }
+            if lines.len() == limit {
+                lines.pop_front();
+            }
+            if limit > 0 {
+                lines.push_back(line.to_string());
+            }
+        }
+    }
+
+    Ok(lines.into())
+}
+
+/// 将所有日志文件按时间顺序合并导出到指定文件
+pub fn export(dir: &Path, dest: &Path) -> Result<(), AppError> {
+    log::logger().flush();
+
+    let mut output = File::create(dest)?;
+    for path in log_files(dir) {
+        output.write_all(&fs::read(&path)?)?;
+    }
+    output.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn log_with_task(logger: &FileLogger, task_id: Option<&str>, message: &str) {
+        let kvs = task_id.map(|id| [("task_id", id)]);
+        let mut builder = Record::builder();
+        builder
+            .level(Level::Info)
+            .target("youtube_downloader_tool::services::download_manager");
+        if let Some(kvs) = kvs.as_ref() {
+            builder.key_values(kvs);
+        }
+        logger.log(&builder.args(format_args!("{}", message)).build());
+    }
+
+    #[test]
+    fn test_log_line_format_and_task_filter() {
+        let temp_dir = TempDir::new().unwrap();
+        let logger = FileLogger::new(temp_dir.path().to_path_buf());
+
+        log_with_task(&logger, Some("task-1"), "开始下载");
+        log_with_task(&logger, None, "调度器启动");
+        log_with_task(&logger, Some("task-2"), "下载完成");
+
+        let all = read_recent(temp_dir.path(), None, 100).unwrap();
+        assert_eq!(all.len(), 3);
+        assert!(all[0].contains(" INFO  [services::download_manager] [task:task-1] 开始下载"));
+        assert!(all[1].ends_with("[services::download_manager] 调度器启动"));
+
+        let task = read_recent(temp_dir.path(), Some("task-2"), 100).unwrap();
+        assert_eq!(task.len(), 1);
+        assert!(task[0].ends_with("下载完成"));
+
+        let last = read_recent(temp_dir.path(), None, 1).unwrap();
+        assert_eq!(last.len(), 1);
+        assert!(last[0].ends_with("下载完成"));
+    }
+
+    #[test]
+    fn test_dependency_debug_logs_are_filtered() {
+        let logger = FileLogger::new(PathBuf::new());
+        let app = Metadata::builder()
+            .level(Level::Debug)
+            .target("youtube_downloader_tool::services::ytdlp_service")
+            .build();
+        let dependency = Metadata::builder().level(Level::Debug).target("hyper::proto").build();
+        let dependency_warn = Metadata::builder().level(Level::Warn).target("hyper::proto").build();
+
+        assert!(logger.enabled(&app));
+        assert!(!logger.enabled(&dependency));
+        assert!(logger.enabled(&dependency_warn));
+    }
+
+    #[test]
+    fn test_rotation_keeps_limited_files_in_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut logger = FileLogger::new(temp_dir.path().to_path_buf());
+        logger.max_size = 64;
+
+        for i in 0..40 {
+            log_with_task(&logger, None, &format!("消息 {:02}", i));
+        }
+
+        let files = log_files(temp_dir.path());
+        assert_eq!(files.len(), MAX_LOG_FILES);
+        assert!(!log_file_path(temp_dir.path(), MAX_LOG_FILES).exists());
+
+        // 读取结果按时间顺序排列，最新的消息在最后
+        let lines = read_recent(temp_dir.path(), None, 1000).unwrap();
+        assert!(lines.last().unwrap().ends_with("消息 39"));
+        let numbers: Vec<u32> = lines
+            .iter()
+            .map(|l| l.rsplit(' ').next().unwrap().parse().unwrap())
+            .collect();
+        assert!(numbers.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_export_concatenates_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let log_dir = temp_dir.path().join("logs");
+        fs::create_dir_all(&log_dir).unwrap();
+        fs::write(log_file_path(&log_dir, 1), "旧日志\n").unwrap();
+        fs::write(log_file_path(&log_dir, 0), "新日志\n").unwrap();
+
+        let dest = temp_dir.path().join("export.log");
+        export(&log_dir, &dest).unwrap();
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "旧日志\n新日志\n");
+    }
+}
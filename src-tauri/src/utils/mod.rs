@@ -1,3 +1,7 @@
 // 工具函数模块
 
 pub mod fs;
+pub mod http;
+pub mod i18n;
+pub mod logger;
+pub mod url;
@@ -0,0 +1,334 @@
+// 视频链接规范化与类型识别
+// 在调用 yt-dlp 之前去掉跟踪参数，把 YouTube 的各种分享链接统一为标准形式
+
+use crate::error::AppError;
+use reqwest::Url;
+use serde::Serialize;
+
+/// 会被去掉的跟踪参数
+const TRACKING_PARAMS: [&str; 6] = ["si", "feature", "pp", "fbclid", "gclid", "igshid"];
+
+/// YouTube 视频 ID 长度
+const YOUTUBE_ID_LEN: usize = 11;
+
+/// 链接类型
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UrlKind {
+    /// 单个视频
+    Video,
+    /// 播放列表
+    Playlist,
+    /// 频道
+    Channel,
+    /// 其他网站的链接，由 yt-dlp 识别
+    Unknown,
+}
+
+/// 规范化后的链接
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NormalizedUrl {
+    /// 规范化后的 URL
+    pub url: String,
+    /// 链接类型
+    pub kind: UrlKind,
+    /// YouTube 视频 ID
+    pub video_id: Option<String>,
+}
+
+impl NormalizedUrl {
+    /// 是否为播放列表或频道链接（包含多个视频）
+    pub fn is_collection(&self) -> bool {
+        matches!(self.kind, UrlKind::Playlist | UrlKind::Channel)
+    }
+
+    /// 去重键：YouTube 视频使用视频 ID，其他链接使用规范化后的 URL
+    pub fn key(&self) -> String {
+        match &self.video_id {
+            Some(id) => format!("youtube:{}", id),
+            None => self.url.clone(),
+        }
+    }
+}
+
+/// 规范化用户输入的链接
+///
+/// 支持单独的 YouTube 视频 ID 和省略协议的链接（如 `youtu.be/xxx`）
+pub fn normalize_url(input: &str) -> Result<NormalizedUrl, AppError> {
+    let input = input.trim();
+    let invalid = || AppError::InvalidUrl(input.to_string());
+
+    if is_bare_youtube_id(input) {
+        return Ok(youtube_video(input));
+    }
+
+    let with_scheme = if input.contains("://") {
+        input.to_string()
+    } else {
+        format!("https://{}", input)
+    };
+    let mut url = Url::parse(&with_scheme).map_err(|_| invalid())?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none_or(|h| !h.contains('.'))
+    {
+        return Err(invalid());
+    }
+    // 省略协议时只接受看起来像域名的输入，避免把普通文本当成链接
+    if !input.contains("://") && input.contains(char::is_whitespace) {
+        return Err(invalid());
+    }
+
+    if let Some(normalized) = normalize_youtube(&url) {
+        return Ok(normalized);
+    }
+
+    strip_tracking_params(&mut url);
+    url.set_fragment(None);
+    Ok(NormalizedUrl {
+        url: url.into(),
+        kind: UrlKind::Unknown,
+        video_id: None,
+    })
+}
+
+/// 规范化单个视频的链接，播放列表和频道链接返回错误
+pub fn normalize_video_url(input: &str) -> Result<NormalizedUrl, AppError> {
+    let normalized = normalize_url(input)?;
+    if normalized.is_collection() {
+        return Err(AppError::PlaylistNotSupported(normalized.url));
+    }
+    Ok(normalized)
+}
+
+/// 计算链接的去重键，无法规范化的链接使用原文
+pub fn url_key(input: &str) -> String {
+    normalize_video_url(input)
+        .map(|normalized| normalized.key())
+        .unwrap_or_else(|_| input.trim().to_string())
+}
+
+/// 是否为 YouTube 视频 ID
+fn is_youtube_id(text: &str) -> bool {
+    text.len() == YOUTUBE_ID_LEN
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// 单独输入的文本是否可以当作 YouTube 视频 ID
+///
+/// 要求包含数字，或同时包含 `-`/`_` 和大写字母，避免把 `description`、`video_title`
+/// 这样的单词当成视频 ID；不符合的视频需要输入完整链接
+fn is_bare_youtube_id(text: &str) -> bool {
+    let has_digit = text.contains(|c: char| c.is_ascii_digit());
+    let has_symbol = text.contains(['-', '_']);
+    let has_upper = text.contains(|c: char| c.is_ascii_uppercase());
+    is_youtube_id(text) && (has_digit || (has_symbol && has_upper))
+}
+
+fn youtube_video(id: &str) -> NormalizedUrl {
+    NormalizedUrl {
+        url: format!("https://www.youtube.com/watch?v={}", id),
+        kind: UrlKind::Video,
+        video_id: Some(id.to_string()),
+    }
+}
+
+/// 识别 YouTube 链接，非 YouTube 链接返回 `None`
+fn normalize_youtube(url: &Url) -> Option<NormalizedUrl> {
+    let host = url.host_str()?.to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let host = host.strip_prefix("m.").unwrap_or(host);
+    let segments: Vec<&str> = url
+        .path_segments()
+        .map(|s| s.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    let query = |key: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.into_owned())
+    };
+
+    let video_id = match (host, segments.as_slice()) {
+        ("youtu.be", [id, ..]) => Some(id.to_string()),
+        ("youtube.com" | "music.youtube.com", ["watch"]) => query("v"),
+        (
+            "youtube.com" | "music.youtube.com" | "youtube-nocookie.com",
+            ["shorts" | "embed" | "live" | "v", id, ..],
+        ) => Some(id.to_string()),
+        ("youtube.com" | "music.youtube.com", ["playlist"]) => {
+            let list = query("list")?;
+            return Some(NormalizedUrl {
+                url: format!("https://www.youtube.com/playlist?list={}", list),
+                kind: UrlKind::Playlist,
+                video_id: None,
+            });
+        }
+        ("youtube.com", [handle, ..]) if handle.starts_with('@') => {
+            return Some(youtube_channel(&[*handle]));
+        }
+        ("youtube.com", [prefix @ ("channel" | "c" | "user"), name, ..]) => {
+            return Some(youtube_channel(&[*prefix, *name]));
+        }
+        ("youtube.com" | "youtu.be" | "music.youtube.com" | "youtube-nocookie.com", _) => None,
+        _ => return None,
+    };
+
+    // 无法识别的 YouTube 链接保持原样（去掉跟踪参数）交给 yt-dlp
+    match video_id.filter(|id| is_youtube_id(id)) {
+        Some(id) => Some(youtube_video(&id)),
+        None => {
+            let mut url = url.clone();
+            strip_tracking_params(&mut url);
+            url.set_fragment(None);
+            Some(NormalizedUrl {
+                url: url.into(),
+                kind: UrlKind::Unknown,
+                video_id: None,
+            })
+        }
+    }
+}
+
+fn youtube_channel(segments: &[&str]) -> NormalizedUrl {
+    NormalizedUrl {
+        url: format!("https://www.youtube.com/{}", segments.join("/")),
+        kind: UrlKind::Channel,
+        video_id: None,
+    }
+}
+
+/// 去掉跟踪参数（包括所有 `utm_*` 参数）
+fn strip_tracking_params(url: &mut Url) {
+    let kept: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| !TRACKING_PARAMS.contains(&key.as_ref()) && !key.starts_with("utm_"))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+
+    if kept.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(kept);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_youtube_video_links_are_canonical() {
+        let expected = "https://www.youtube.com/watch?v=dQw4w9WgXcQ";
+        for input in [
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+            "https://youtube.com/watch?feature=share&v=dQw4w9WgXcQ&t=42",
+            "https://m.youtube.com/watch?v=dQw4w9WgXcQ",
+            "https://youtu.be/dQw4w9WgXcQ?si=abcdef",
+            "youtu.be/dQw4w9WgXcQ",
+            "https://www.youtube.com/shorts/dQw4w9WgXcQ?feature=share",
+            "https://www.youtube.com/embed/dQw4w9WgXcQ",
+            "https://www.youtube.com/live/dQw4w9WgXcQ",
+            "https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ",
+            " https://music.youtube.com/watch?v=dQw4w9WgXcQ#comments ",
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=PL123&index=2",
+            "dQw4w9WgXcQ",
+        ] {
+            let normalized = normalize_url(input).unwrap();
+            assert_eq!(normalized.url, expected, "{}", input);
+            assert_eq!(normalized.kind, UrlKind::Video);
+            assert_eq!(normalized.video_id.as_deref(), Some("dQw4w9WgXcQ"));
+        }
+    }
+
+    #[test]
+    fn test_playlist_and_channel_links() {
+        let playlist = normalize_url("https://www.youtube.com/playlist?list=PL123&si=x").unwrap();
+        assert_eq!(playlist.kind, UrlKind::Playlist);
+        assert_eq!(playlist.url, "https://www.youtube.com/playlist?list=PL123");
+        assert!(playlist.is_collection());
+
+        for (input, expected) in [
+            ("https://www.youtube.com/@SomeChannel/videos", "https://www.youtube.com/@SomeChannel"),
+            (
+                "https://m.youtube.com/channel/UC1234567890",
+                "https://www.youtube.com/channel/UC1234567890",
+            ),
+            ("https://youtube.com/c/SomeName", "https://www.youtube.com/c/SomeName"),
+            ("https://youtube.com/user/SomeName/", "https://www.youtube.com/user/SomeName"),
+        ] {
+            let channel = normalize_url(input).unwrap();
+            assert_eq!(channel.kind, UrlKind::Channel, "{}", input);
+            assert_eq!(channel.url, expected);
+            assert!(matches!(
+                normalize_video_url(input),
+                Err(AppError::PlaylistNotSupported(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_other_sites_keep_path_and_strip_tracking() {
+        let normalized =
+            normalize_url("https://www.bilibili.com/video/BV1xx?p=2&utm_source=share&si=1#t=5")
+                .unwrap();
+        assert_eq!(normalized.kind, UrlKind::Unknown);
+        assert_eq!(normalized.url, "https://www.bilibili.com/video/BV1xx?p=2");
+        assert_eq!(normalized.video_id, None);
+
+        let plain = normalize_url("https://vimeo.com/123456?fbclid=abc").unwrap();
+        assert_eq!(plain.url, "https://vimeo.com/123456");
+    }
+
+    #[test]
+    fn test_url_key() {
+        assert_eq!(url_key("https://youtu.be/dQw4w9WgXcQ?t=1"), "youtube:dQw4w9WgXcQ");
+        assert_eq!(url_key("https://vimeo.com/1#t=5"), "https://vimeo.com/1");
+        assert_eq!(url_key(" not a url "), "not a url");
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        for input in [
+            "",
+            "not a url at all",
+            "ftp://example.com/video",
+            "file:///path/to/video",
+            "javascript:alert(1)",
+            "https://localhost/video",
+            "dQw4w9WgXc",
+            "description",
+            "downloading",
+            "video_title",
+        ] {
+            assert!(
+                matches!(normalize_url(input), Err(AppError::InvalidUrl(_))),
+                "{}",
+                input
+            );
+        }
+    }
+
+    proptest! {
+        // 规范化是幂等的
+        #[test]
+        fn prop_normalize_is_idempotent(
+            id in "[A-Za-z0-9_-]{11}",
+            form in 0usize..4,
+            tracking in proptest::bool::ANY,
+        ) {
+            let suffix = if tracking { "?si=abc&utm_source=x" } else { "" };
+            let input = match form {
+                0 => format!("https://youtu.be/{}{}", id, suffix),
+                1 => format!("https://www.youtube.com/shorts/{}{}", id, suffix),
+                2 => format!("https://vimeo.com/{}{}", id, suffix),
+                // 单独的视频 ID 需要包含数字
+                _ => format!("{}0", &id[..10]),
+            };
+            let once = normalize_url(&input).unwrap();
+            let twice = normalize_url(&once.url).unwrap();
+            prop_assert_eq!(once, twice);
+        }
+    }
+}
@@ -23,12 +23,14 @@ vi.mock('./services/tauriApi', () => ({
     event: {
       onDownloadProgress: vi.fn(() => Promise.resolve(() => {})),
       onDownloadComplete: vi.fn(() => Promise.resolve(() => {})),
+      onDownloadSkipped: vi.fn(() => Promise.resolve(() => {})),
       onDownloadError: vi.fn(() => Promise.resolve(() => {})),
     },
   },
   eventApi: {
     onDownloadProgress: vi.fn(() => Promise.resolve(() => {})),
     onDownloadComplete: vi.fn(() => Promise.resolve(() => {})),
+    onDownloadSkipped: vi.fn(() => Promise.resolve(() => {})),
     onDownloadError: vi.fn(() => Promise.resolve(() => {})),
   },
 }));
@@ -18,7 +18,7 @@ import { UpdatePanel } from '@/components/UpdatePanel';
 import { Toaster } from '@/components/ui/toaster';
 import { useDownloadStore } from '@/store/downloadStore';
 import { useConfigStore } from '@/store/configStore';
-import { TaskStatus, type HistoryItem } from '@/types';
+import { TaskStatus } from '@/types';
 import logger from '@/utils/logger';
 
 /**
@@ -186,48 +186,24 @@ function App() {
           logger.info('下载完成', { taskId: data.taskId, filePath: data.filePath });
           updateTaskStatus(data.taskId, TaskStatus.Completed);
           
-          // 保存到历史记录
-          try {
-            // 从 store 中获取最新的任务信息
-            const currentTasks = useDownloadStore.getState().tasks;
-            const task = currentTasks.find(t => t.id === data.taskId);
-            
-            if (task) {
-              const { historyApi } = await import('@/services/tauriApi');
-              const { useHistoryStore } = await import('@/store/historyStore');
-              
-              // 构造历史记录项
-              const historyItem: HistoryItem = {
-                id: `history-${Date.now()}-${Math.random().toString(36).substr(2, 9)}`,
-                title: task.title,
-                url: task.url,
-                resolution: task.formatId,
-                filePath: data.filePath,
-                fileSize: data.fileSize,
-                downloadedAt: Date.now(),
-                fileExists: true,
-              };
-              
-              // 保存到后端
-              await historyApi.addHistory(historyItem);
-              
-              // 同时更新前端 store 以立即显示
-              useHistoryStore.getState().addHistoryItem(historyItem);
-              
-              logger.info('历史记录已保存', { historyItem });
-            } else {
-              logger.warn('未找到任务信息，无法保存历史记录', { taskId: data.taskId });
-            }
-          } catch (error) {
-            logger.error('保存历史记录失败', error instanceof Error ? error : undefined, { 
-              taskId: data.taskId,
-              error: error instanceof Error ? error.message : String(error)
-            });
+          // 历史记录已由后端保存，这里只更新前端 store 以立即显示
+          if (data.historyItem) {
+            const { useHistoryStore } = await import('@/store/historyStore');
+            useHistoryStore.getState().addHistoryItem(data.historyItem);
           }
         });
         return unlisten;
       };
 
+      // 监听下载跳过（下载存档中已有该视频，不写入历史记录）
+      const setupSkippedListener = async () => {
+        const unlisten = await eventApi.onDownloadSkipped((data) => {
+          logger.info('下载存档中已有该视频，已跳过', { taskId: data.taskId });
+          updateTaskStatus(data.taskId, TaskStatus.Skipped);
+        });
+        return unlisten;
+      };
+
       // 监听下载错误
       const setupErrorListener = async () => {
         const unlisten = await eventApi.onDownloadError((data) => {
@@ -241,6 +217,7 @@ function App() {
       Promise.all([
         setupProgressListener(),
         setupCompleteListener(),
+        setupSkippedListener(),
         setupErrorListener(),
       ]).then((unlisteners) => {
         logger.info('下载事件监听器已设置');
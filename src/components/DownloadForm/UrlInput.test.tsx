@@ -58,6 +58,19 @@ describe('UrlInput', () => {
       expect(mockOnSubmit).toHaveBeenCalledWith('https://www.bilibili.com/video/BV1234567890');
     });
 
+    it('应该在输入单独的 YouTube 视频 ID 时调用 onSubmit', () => {
+      render(<UrlInput onSubmit={mockOnSubmit} />);
+      
+      const input = screen.getByLabelText('视频链接输入框');
+      const button = screen.getByRole('button', { name: /获取信息/ });
+      
+      fireEvent.change(input, { target: { value: 'dQw4w9WgXcQ' } });
+      fireEvent.click(button);
+      
+      expect(mockOnSubmit).toHaveBeenCalledTimes(1);
+      expect(mockOnSubmit).toHaveBeenCalledWith('dQw4w9WgXcQ');
+    });
+
     it('应该在输入带有空格的有效 URL 时自动去除空格并提交', () => {
       render(<UrlInput onSubmit={mockOnSubmit} />);
       
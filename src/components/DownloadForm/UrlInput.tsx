@@ -13,7 +13,7 @@ interface UrlInputProps {
  * 
  * 功能：
  * - 提供 URL 输入框
- * - 基本的 HTTP/HTTPS 格式验证
+ * - 基本的 HTTP/HTTPS 格式验证（也接受单独的 YouTube 视频 ID）
  * - 提交处理
  * 
  * 需求：1.1, 1.4
@@ -23,7 +23,7 @@ export function UrlInput({ onSubmit, isLoading = false, disabled = false }: UrlI
   const [error, setError] = useState('');
 
   /**
-   * 验证 URL 格式（基本 HTTP/HTTPS 检查）
+   * 验证 URL 格式（基本 HTTP/HTTPS 检查，完整的规范化由后端完成）
    */
   const validateUrl = (input: string): boolean => {
     if (!input.trim()) {
@@ -33,7 +33,8 @@ export function UrlInput({ onSubmit, isLoading = false, disabled = false }: UrlI
 
     // 基本的 HTTP/HTTPS 检查
     const urlPattern = /^https?:\/\/.+/i;
-    if (!urlPattern.test(input.trim())) {
+    const videoIdPattern = /^[A-Za-z0-9_-]{11}$/;
+    if (!urlPattern.test(input.trim()) && !videoIdPattern.test(input.trim())) {
       setError('请输入有效的 HTTP 或 HTTPS 链接');
       return false;
     }
@@ -46,12 +46,17 @@ describe('HistoryList', () => {
     // 默认 mock 实现
     vi.mocked(useHistoryStore).mockReturnValue({
       items: mockItems,
+      total: mockItems.length,
+      nextCursor: null,
+      loadMore: vi.fn(),
       isLoading: false,
       error: null,
+      recoveries: [],
       loadHistory: mockLoadHistory,
       clearHistory: mockClearHistory,
       openHistoryFile: mockOpenHistoryFile,
       clearError: mockClearError,
+      dismissRecoveries: vi.fn(),
       addHistoryItem: vi.fn(),
       updateFileExists: vi.fn(),
       refreshFileExistence: vi.fn(),
@@ -78,12 +83,17 @@ describe('HistoryList', () => {
   it('应该在加载时显示加载状态', () => {
     vi.mocked(useHistoryStore).mockReturnValue({
       items: [],
+      total: 0,
+      nextCursor: null,
+      loadMore: vi.fn(),
       isLoading: true,
       error: null,
+      recoveries: [],
       loadHistory: mockLoadHistory,
       clearHistory: mockClearHistory,
       openHistoryFile: mockOpenHistoryFile,
       clearError: mockClearError,
+      dismissRecoveries: vi.fn(),
       addHistoryItem: vi.fn(),
       updateFileExists: vi.fn(),
       refreshFileExistence: vi.fn(),
@@ -96,12 +106,17 @@ describe('HistoryList', () => {
   it('应该在出错时显示错误信息', () => {
     vi.mocked(useHistoryStore).mockReturnValue({
       items: [],
+      total: 0,
+      nextCursor: null,
+      loadMore: vi.fn(),
       isLoading: false,
       error: '加载失败',
+      recoveries: [],
       loadHistory: mockLoadHistory,
       clearHistory: mockClearHistory,
       openHistoryFile: mockOpenHistoryFile,
       clearError: mockClearError,
+      dismissRecoveries: vi.fn(),
       addHistoryItem: vi.fn(),
       updateFileExists: vi.fn(),
       refreshFileExistence: vi.fn(),
@@ -114,12 +129,17 @@ describe('HistoryList', () => {
   it('应该在列表为空时显示空状态', () => {
     vi.mocked(useHistoryStore).mockReturnValue({
       items: [],
+      total: 0,
+      nextCursor: null,
+      loadMore: vi.fn(),
       isLoading: false,
       error: null,
+      recoveries: [],
       loadHistory: mockLoadHistory,
       clearHistory: mockClearHistory,
       openHistoryFile: mockOpenHistoryFile,
       clearError: mockClearError,
+      dismissRecoveries: vi.fn(),
       addHistoryItem: vi.fn(),
       updateFileExists: vi.fn(),
       refreshFileExistence: vi.fn(),
@@ -164,15 +184,50 @@ describe('HistoryList', () => {
     confirmSpy.mockRestore();
   });
 
+  it('应该显示历史记录恢复提示', () => {
+    const mockDismissRecoveries = vi.fn();
+    vi.mocked(useHistoryStore).mockReturnValue({
+      items: mockItems,
+      total: mockItems.length,
+      nextCursor: null,
+      loadMore: vi.fn(),
+      isLoading: false,
+      error: null,
+      recoveries: [
+        { backupPath: '/data/history.json.backup', recovered: 3, lost: 2 },
+      ],
+      loadHistory: mockLoadHistory,
+      clearHistory: mockClearHistory,
+      openHistoryFile: mockOpenHistoryFile,
+      clearError: mockClearError,
+      dismissRecoveries: mockDismissRecoveries,
+      addHistoryItem: vi.fn(),
+      updateFileExists: vi.fn(),
+      refreshFileExistence: vi.fn(),
+    });
+
+    render(<HistoryList />);
+
+    expect(screen.getByText(/已恢复 3 条记录/)).toBeInTheDocument();
+    expect(screen.getByText(/约 2 条记录丢失/)).toBeInTheDocument();
+    fireEvent.click(screen.getByRole('button', { name: '知道了' }));
+    expect(mockDismissRecoveries).toHaveBeenCalled();
+  });
+
   it('应该在列表为空时禁用清空按钮', () => {
     vi.mocked(useHistoryStore).mockReturnValue({
       items: [],
+      total: 0,
+      nextCursor: null,
+      loadMore: vi.fn(),
       isLoading: false,
       error: null,
+      recoveries: [],
       loadHistory: mockLoadHistory,
       clearHistory: mockClearHistory,
       openHistoryFile: mockOpenHistoryFile,
       clearError: mockClearError,
+      dismissRecoveries: vi.fn(),
       addHistoryItem: vi.fn(),
       updateFileExists: vi.fn(),
       refreshFileExistence: vi.fn(),
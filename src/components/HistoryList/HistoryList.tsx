@@ -21,12 +21,18 @@ interface HistoryListProps {
 export const HistoryList: React.FC<HistoryListProps> = ({ height: _height = 600 }) => {
   const {
     items,
+    total,
+    nextCursor,
     isLoading,
     error,
+    recoveries,
     loadHistory,
+    loadMore,
     clearHistory,
     openHistoryFile,
     clearError,
+    dismissRecoveries,
+    refreshFileExistence,
   } = useHistoryStore();
 
   const [isClearing, setIsClearing] = React.useState(false);
@@ -65,6 +71,32 @@ export const HistoryList: React.FC<HistoryListProps> = ({ height: _height = 600
     }
   };
 
+  // 渲染历史记录恢复提示
+  const renderRecoveries = () => {
+    if (recoveries.length === 0) {
+      return null;
+    }
+
+    return (
+      <div className="mb-4 rounded-md border border-yellow-500/50 bg-yellow-500/10 p-3 text-sm">
+        {recoveries.map((recovery) => (
+          <p key={recovery.backupPath}>
+            历史记录文件已损坏，已恢复 {recovery.recovered} 条记录
+            {recovery.lost !== null
+              ? `，约 ${recovery.lost} 条记录丢失`
+              : '，最近的部分记录可能已丢失'}
+            。原文件已备份到 {recovery.backupPath}
+          </p>
+        ))}
+        <div className="mt-2 text-right">
+          <Button variant="outline" size="sm" onClick={dismissRecoveries}>
+            知道了
+          </Button>
+        </div>
+      </div>
+    );
+  };
+
   // 渲染内容
   const renderContent = () => {
     if (isLoading) {
@@ -100,6 +132,13 @@ export const HistoryList: React.FC<HistoryListProps> = ({ height: _height = 600
         {items.map((item) => (
           <HistoryItem key={item.id} item={item} onOpenFile={handleOpenFile} />
         ))}
+        {nextCursor && (
+          <div className="text-center py-2">
+            <Button variant="ghost" size="sm" onClick={() => loadMore()}>
+              加载更多
+            </Button>
+          </div>
+        )}
       </div>
     );
   };
@@ -111,8 +150,16 @@ export const HistoryList: React.FC<HistoryListProps> = ({ height: _height = 600
           <CardTitle>下载历史</CardTitle>
           <div className="flex items-center gap-2">
             <span className="text-sm text-muted-foreground">
-              共 {items.length} 条记录
+              共 {total} 条记录
             </span>
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={() => refreshFileExistence()}
+              disabled={isLoading || items.length === 0}
+            >
+              检查文件
+            </Button>
             <Button
               variant="outline"
               size="sm"
@@ -124,7 +171,10 @@ export const HistoryList: React.FC<HistoryListProps> = ({ height: _height = 600
           </div>
         </div>
       </CardHeader>
-      <CardContent>{renderContent()}</CardContent>
+      <CardContent>
+        {renderRecoveries()}
+        {renderContent()}
+      </CardContent>
     </Card>
   );
 };
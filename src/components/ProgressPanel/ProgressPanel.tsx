@@ -74,6 +74,7 @@ export const ProgressPanel: React.FC<ProgressPanelProps> = ({
   useEffect(() => {
     let unlistenProgress: (() => void) | null = null;
     let unlistenComplete: (() => void) | null = null;
+    let unlistenSkipped: (() => void) | null = null;
     let unlistenError: (() => void) | null = null;
 
     const setupListeners = async () => {
@@ -95,6 +96,13 @@ export const ProgressPanel: React.FC<ProgressPanelProps> = ({
         }
       });
 
+      // 监听下载跳过
+      unlistenSkipped = await eventApi.onDownloadSkipped((data) => {
+        if (data.taskId === taskId) {
+          updateTaskStatus(taskId, TaskStatus.Skipped);
+        }
+      });
+
       // 监听下载错误
       unlistenError = await eventApi.onDownloadError((data) => {
         if (data.taskId === taskId) {
@@ -121,6 +129,7 @@ export const ProgressPanel: React.FC<ProgressPanelProps> = ({
       // 取消事件监听
       unlistenProgress?.();
       unlistenComplete?.();
+      unlistenSkipped?.();
       unlistenError?.();
     };
   }, [taskId, debouncedUpdate, updateProgress, updateTaskStatus]);
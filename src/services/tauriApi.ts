@@ -11,6 +11,18 @@ import type {
   AppConfig,
   HistoryItem,
   UpdateInfo,
+  ErrorResponse,
+  TaskLog,
+  ImportReport,
+  DuplicateCheck,
+  DuplicateAction,
+  HistoryQuery,
+  HistoryPage,
+  DeleteResult,
+  RelinkReport,
+  HistoryFormat,
+  MergeStrategy,
+  HistoryImportResult,
 } from '../types';
 
 /**
@@ -20,18 +32,44 @@ export class TauriCommandError extends Error {
   constructor(
     message: string,
     public readonly command: string,
-    public readonly originalError?: unknown
+    public readonly originalError?: unknown,
+    /** 后端错误代码（如 "InsufficientSpace"） */
+    public readonly errorType?: string,
+    /** 是否可以重试 */
+    public readonly retryable: boolean = false
   ) {
     super(message);
     this.name = 'TauriCommandError';
   }
 }
 
+/**
+ * 判断是否为后端返回的错误响应
+ */
+function isErrorResponse(error: unknown): error is ErrorResponse {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as ErrorResponse).errorType === 'string' &&
+    typeof (error as ErrorResponse).message === 'string'
+  );
+}
+
 /**
  * 统一的错误处理函数
  */
 function handleError(command: string, error: unknown): never {
   console.error(`Tauri 命令执行失败 [${command}]:`, error);
+
+  if (isErrorResponse(error)) {
+    throw new TauriCommandError(
+      error.message,
+      command,
+      error,
+      error.errorType,
+      error.retryable
+    );
+  }
   
   const message = typeof error === 'string' 
     ? error 
@@ -64,12 +102,14 @@ export const videoApi = {
    * @param url 视频 URL
    * @param formatId 格式 ID
    * @param savePath 保存路径
+   * @param onDuplicate 已下载过同一视频时的处理方式
    * @returns 任务 ID
    */
   async downloadVideo(
     url: string,
     formatId: string,
-    savePath: string
+    savePath: string,
+    onDuplicate?: DuplicateAction
   ): Promise<string> {
     try {
       // 生成任务 ID
@@ -82,6 +122,7 @@ export const videoApi = {
           format_id: formatId,
           save_path: savePath,
           task_id: taskId,
+          ...(onDuplicate && { on_duplicate: onDuplicate }),
         }
       };
       
@@ -106,6 +147,19 @@ export const videoApi = {
       handleError('cancel_download', error);
     }
   },
+
+  /**
+   * 批量导入链接
+   * @param source 粘贴的文本，或 .txt/.csv 文件路径
+   * @returns 逐行的校验结果和可以下载的链接
+   */
+  async importUrls(source: { text: string } | { filePath: string }): Promise<ImportReport> {
+    try {
+      return await invoke<ImportReport>('import_urls', source);
+    } catch (error) {
+      handleError('import_urls', error);
+    }
+  },
 };
 
 /**
@@ -182,6 +236,118 @@ export const historyApi = {
     }
   },
 
+  /**
+   * 按条件查询历史记录
+   * @param query 搜索、筛选、排序和分页条件
+   * @returns 一页历史记录
+   */
+  async queryHistory(query: HistoryQuery): Promise<HistoryPage> {
+    try {
+      return await invoke<HistoryPage>('query_history', { query });
+    } catch (error) {
+      handleError('query_history', error);
+    }
+  },
+
+  /**
+   * 删除选中的历史记录
+   * @param ids 记录 ID
+   * @param deleteFiles 是否同时删除媒体文件及其附属文件
+   * @returns 每条记录的删除结果
+   */
+  async deleteHistoryItems(ids: string[], deleteFiles: boolean): Promise<DeleteResult[]> {
+    try {
+      return await invoke<DeleteResult[]>('delete_history_items', { ids, deleteFiles });
+    } catch (error) {
+      handleError('delete_history_items', error);
+    }
+  },
+
+  /**
+   * 清理历史记录
+   * @param missingFiles 是否删除文件已不存在的记录
+   * @param olderThanDays 删除早于此天数的记录
+   * @returns 删除的条数
+   */
+  async pruneHistory(missingFiles: boolean, olderThanDays?: number): Promise<number> {
+    try {
+      return await invoke<number>('prune_history', {
+        missingFiles,
+        olderThanDays: olderThanDays ?? null,
+      });
+    } catch (error) {
+      handleError('prune_history', error);
+    }
+  },
+
+  /**
+   * 重新检查所有历史记录的文件是否存在
+   * @returns 检查结果发生变化的记录数
+   */
+  async refreshHistoryFiles(): Promise<number> {
+    try {
+      return await invoke<number>('refresh_history_files');
+    } catch (error) {
+      handleError('refresh_history_files', error);
+    }
+  },
+
+  /**
+   * 下载文件夹移动后更新历史记录中的文件路径
+   * @param oldRoot 原来的文件夹
+   * @param newRoot 新的文件夹
+   * @returns 修改的记录数
+   */
+  async relocateHistory(oldRoot: string, newRoot: string): Promise<number> {
+    try {
+      return await invoke<number>('relocate_history', { oldRoot, newRoot });
+    } catch (error) {
+      handleError('relocate_history', error);
+    }
+  },
+
+  /**
+   * 在目录下按文件名和大小查找丢失的文件并重新关联
+   * @param dir 要扫描的目录
+   * @returns 关联结果
+   */
+  async relinkHistory(dir: string): Promise<RelinkReport> {
+    try {
+      return await invoke<RelinkReport>('relink_history', { dir });
+    } catch (error) {
+      handleError('relink_history', error);
+    }
+  },
+
+  /**
+   * 导出历史记录，可用查询条件只导出部分记录，返回导出的条数
+   */
+  async exportHistory(
+    format: HistoryFormat,
+    path: string,
+    query?: HistoryQuery
+  ): Promise<number> {
+    try {
+      return await invoke<number>('export_history', { format, path, query: query ?? null });
+    } catch (error) {
+      handleError('export_history', error);
+    }
+  },
+
+  /**
+   * 从导出的 JSON 或 CSV 文件导入历史记录
+   */
+  async importHistory(
+    path: string,
+    mergeStrategy: MergeStrategy = 'skip'
+  ): Promise<HistoryImportResult> {
+    try {
+      return await invoke<HistoryImportResult>('import_history', { path, mergeStrategy });
+    } catch (error) {
+      handleError('import_history', error);
+    }
+  },
+
   /**
    * 清空历史记录
    */
@@ -194,14 +360,84 @@ export const historyApi = {
   },
 
   /**
-   * 添加历史记录
-   * @param item 历史记录项
+   * 检查是否已下载过同一视频的同一格式
+   * @param url 视频 URL
+   * @param formatId 格式 ID
+   * @returns 检查结果
    */
-  async addHistory(item: HistoryItem): Promise<void> {
+  async checkDuplicate(url: string, formatId: string): Promise<DuplicateCheck> {
     try {
-      await invoke<void>('add_history', { item });
+      return await invoke<DuplicateCheck>('check_duplicate', { url, formatId });
     } catch (error) {
-      handleError('add_history', error);
+      handleError('check_duplicate', error);
+    }
+  },
+
+  /**
+   * 导入 yt-dlp 下载存档文件
+   * @returns 新增的历史记录条数，取消选择时返回 null
+   */
+  async importDownloadArchive(): Promise<number | null> {
+    try {
+      return await invoke<number | null>('import_download_archive');
+    } catch (error) {
+      handleError('import_download_archive', error);
+    }
+  },
+
+  /**
+   * 把历史记录导出为 yt-dlp 下载存档文件
+   * @returns 导出的文件路径，取消时返回 null
+   */
+  async exportDownloadArchive(): Promise<string | null> {
+    try {
+      return await invoke<string | null>('export_download_archive');
+    } catch (error) {
+      handleError('export_download_archive', error);
+    }
+  },
+};
+
+/**
+ * 日志相关 API
+ */
+export const logApi = {
+  /**
+   * 获取最近的日志
+   * @param taskId 只返回指定任务的日志
+   * @param limit 返回的最大行数
+   * @returns 日志行（从旧到新）
+   */
+  async getLogs(taskId?: string, limit?: number): Promise<string[]> {
+    try {
+      return await invoke<string[]>('get_logs', { taskId, limit });
+    } catch (error) {
+      handleError('get_logs', error);
+    }
+  },
+
+  /**
+   * 导出日志文件
+   * @returns 导出的文件路径，用户取消时返回 null
+   */
+  async exportLogs(): Promise<string | null> {
+    try {
+      return await invoke<string | null>('export_logs');
+    } catch (error) {
+      handleError('export_logs', error);
+    }
+  },
+
+  /**
+   * 获取任务的 yt-dlp 输出记录
+   * @param taskId 任务 ID
+   * @returns 执行的命令行和输出
+   */
+  async getTaskLog(taskId: string): Promise<TaskLog> {
+    try {
+      return await invoke<TaskLog>('get_task_log', { taskId });
+    } catch (error) {
+      handleError('get_task_log', error);
     }
   },
 };
@@ -282,6 +518,7 @@ export const eventApi = {
       taskId: string;
       filePath: string;
       fileSize: number;
+      historyItem: HistoryItem | null;
     }>
   ): Promise<UnlistenFn> {
     try {
@@ -289,6 +526,7 @@ export const eventApi = {
         taskId: string;
         filePath: string;
         fileSize: number;
+        historyItem: HistoryItem | null;
       }>('download-complete', (event) => {
         callback(event.payload);
       });
@@ -297,6 +535,27 @@ export const eventApi = {
     }
   },
 
+  /**
+   * 监听下载跳过事件（下载存档中已有该视频）
+   * @param callback 回调函数
+   * @returns 取消监听函数
+   */
+  async onDownloadSkipped(
+    callback: EventCallback<{
+      taskId: string;
+    }>
+  ): Promise<UnlistenFn> {
+    try {
+      return await listen<{
+        taskId: string;
+      }>('download-skipped', (event) => {
+        callback(event.payload);
+      });
+    } catch (error) {
+      handleError('listen:download-skipped', error);
+    }
+  },
+
   /**
    * 监听下载错误事件
    * @param callback 回调函数
@@ -344,6 +603,29 @@ export const eventApi = {
       handleError('listen:update-progress', error);
     }
   },
+
+  /**
+   * 监听剪贴板中检测到视频链接的事件
+   * @param callback 回调函数
+   * @returns 取消监听函数
+   */
+  async onClipboardUrlDetected(
+    callback: EventCallback<{
+      url: string;
+      info: VideoInfo;
+    }>
+  ): Promise<UnlistenFn> {
+    try {
+      return await listen<{
+        url: string;
+        info: VideoInfo;
+      }>('clipboard-url-detected', (event) => {
+        callback(event.payload);
+      });
+    } catch (error) {
+      handleError('listen:clipboard-url-detected', error);
+    }
+  },
 };
 
 /**
@@ -354,6 +636,7 @@ export const tauriApi = {
   fileSystem: fileSystemApi,
   config: configApi,
   history: historyApi,
+  log: logApi,
   update: updateApi,
   event: eventApi,
 };
@@ -4,27 +4,38 @@
  */
 
 import { create } from 'zustand';
-import type { HistoryItem } from '../types';
+import type { HistoryItem, HistoryRecovery } from '../types';
 import { historyApi, fileSystemApi } from '../services/tauriApi';
 
+/** 每页加载的历史记录条数 */
+const HISTORY_PAGE_SIZE = 50;
+
 /**
  * 历史记录 Store 状态接口
  */
 interface HistoryState {
-  /** 历史记录列表 */
+  /** 已加载的历史记录（最新的在前面） */
   items: HistoryItem[];
+  /** 历史记录总条数 */
+  total: number;
+  /** 下一页的游标，没有更多记录时为空 */
+  nextCursor: string | null;
   /** 是否正在加载历史记录 */
   isLoading: boolean;
   /** 历史记录加载/操作错误 */
   error: string | null;
+  /** 尚未被用户关闭的历史记录恢复结果 */
+  recoveries: HistoryRecovery[];
 }
 
 /**
  * 历史记录 Store Actions 接口
  */
 interface HistoryActions {
-  /** 加载历史记录 */
+  /** 加载第一页历史记录 */
   loadHistory: () => Promise<void>;
+  /** 加载下一页历史记录 */
+  loadMore: () => Promise<void>;
   /** 清空历史记录 */
   clearHistory: () => Promise<void>;
   /** 添加历史记录项 */
@@ -37,6 +48,8 @@ interface HistoryActions {
   refreshFileExistence: () => Promise<void>;
   /** 清除错误 */
   clearError: () => void;
+  /** 关闭历史记录恢复提示 */
+  dismissRecoveries: () => void;
 }
 
 /**
@@ -50,15 +63,26 @@ type HistoryStore = HistoryState & HistoryActions;
 export const useHistoryStore = create<HistoryStore>((set, get) => ({
   // 初始状态
   items: [],
+  total: 0,
+  nextCursor: null,
   isLoading: false,
   error: null,
+  recoveries: [],
 
   // Actions
   loadHistory: async () => {
     set({ isLoading: true, error: null });
     try {
-      const items = await historyApi.getHistory();
-      set({ items, isLoading: false });
+      // 按页加载，后端只检查返回的记录的文件是否存在
+      const page = await historyApi.queryHistory({ limit: HISTORY_PAGE_SIZE });
+      set((state) => ({
+        items: page.items,
+        total: page.total,
+        nextCursor: page.nextCursor,
+        isLoading: false,
+        // 恢复结果只返回一次，保留到用户关闭提示
+        recoveries: [...state.recoveries, ...page.recoveries],
+      }));
     } catch (error) {
       const errorMessage =
         error instanceof Error ? error.message : '加载历史记录失败';
@@ -67,11 +91,35 @@ export const useHistoryStore = create<HistoryStore>((set, get) => ({
     }
   },
 
+  loadMore: async () => {
+    const { nextCursor } = get();
+    if (!nextCursor) {
+      return;
+    }
+    try {
+      const page = await historyApi.queryHistory({
+        cursor: nextCursor,
+        limit: HISTORY_PAGE_SIZE,
+      });
+      set((state) => ({
+        items: [...state.items, ...page.items],
+        total: page.total,
+        nextCursor: page.nextCursor,
+        recoveries: [...state.recoveries, ...page.recoveries],
+      }));
+    } catch (error) {
+      const errorMessage =
+        error instanceof Error ? error.message : '加载历史记录失败';
+      set({ error: errorMessage });
+      console.error('加载历史记录失败:', error);
+    }
+  },
+
   clearHistory: async () => {
     set({ isLoading: true, error: null });
     try {
       await historyApi.clearHistory();
-      set({ items: [], isLoading: false });
+      set({ items: [], total: 0, nextCursor: null, isLoading: false });
     } catch (error) {
       const errorMessage =
         error instanceof Error ? error.message : '清空历史记录失败';
@@ -84,6 +132,7 @@ export const useHistoryStore = create<HistoryStore>((set, get) => ({
   addHistoryItem: (item) => {
     set((state) => ({
       items: [item, ...state.items],
+      total: state.total + 1,
     }));
   },
 
@@ -108,17 +157,23 @@ export const useHistoryStore = create<HistoryStore>((set, get) => ({
   },
 
   refreshFileExistence: async () => {
-    // 注意：这个功能需要后端支持批量检查文件存在性
-    // 目前我们只能在前端通过尝试打开文件来检查
-    // 这里提供一个占位实现，实际可能需要后端 API 支持
-    const items = get().items;
-    
-    // 简单实现：假设所有文件都存在，除非打开失败
-    // 更好的实现需要后端提供批量检查 API
-    console.log('刷新文件存在状态 (需要后端支持):', items.length);
+    try {
+      // 查询只检查返回的记录，这里让后端重新检查所有记录后再重新加载
+      await historyApi.refreshHistoryFiles();
+      await get().loadHistory();
+    } catch (error) {
+      const errorMessage =
+        error instanceof Error ? error.message : '检查文件失败';
+      set({ error: errorMessage });
+      console.error('检查文件失败:', error);
+    }
   },
 
   clearError: () => {
     set({ error: null });
   },
+
+  dismissRecoveries: () => {
+    set({ recoveries: [] });
+  },
 }));
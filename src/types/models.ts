@@ -65,6 +65,8 @@ export enum TaskStatus {
   Completed = 'completed',
   Failed = 'failed',
   Cancelled = 'cancelled',
+  /** 下载存档中已有该视频，已跳过 */
+  Skipped = 'skipped',
 }
 
 /**
@@ -114,6 +116,8 @@ export interface HistoryItem {
   id: string;
   /** 视频标题 */
   title: string;
+  /** 上传者 */
+  uploader?: string;
   /** 视频 URL */
   url: string;
   /** 下载的分辨率 */
@@ -126,8 +130,152 @@ export interface HistoryItem {
   downloadedAt: number;
   /** 文件是否存在 */
   fileExists: boolean;
+  /** 下载存档标识（`<提取器> <视频 ID>`） */
+  archiveId?: string | null;
+  /** 下载时选择的格式 ID */
+  formatId?: string;
+  /** 时长（秒） */
+  duration?: number;
+  /** 缩略图 URL */
+  thumbnail?: string;
+  /** 来源网站的提取器 */
+  extractor?: string;
+  /** 视频 ID */
+  videoId?: string;
+  /** 视频编码 */
+  vcodec?: string;
+  /** 音频编码 */
+  acodec?: string;
+  /** 本地缩略图文件 */
+  thumbnailPath?: string | null;
+  /** 字幕文件 */
+  subtitleFiles?: string[];
+  /** 标签 */
+  tags?: string[];
+}
+
+/**
+ * 历史记录排序方式
+ */
+export type HistorySort = 'newest' | 'oldest' | 'largest' | 'smallest' | 'title';
+
+/**
+ * 历史记录查询条件
+ */
+export interface HistoryQuery {
+  /** 搜索关键词（匹配标题、上传者和链接） */
+  search?: string;
+  /** 下载时间下限（含） */
+  from?: number;
+  /** 下载时间上限（含） */
+  to?: number;
+  /** 分辨率 */
+  resolution?: string;
+  /** 文件是否存在 */
+  fileExists?: boolean;
+  /** 文件大小下限（字节） */
+  minSize?: number;
+  /** 文件大小上限（字节） */
+  maxSize?: number;
+  /** 排序方式，默认从新到旧 */
+  sort?: HistorySort;
+  /** 上一页返回的游标 */
+  cursor?: string | null;
+  /** 每页条数 */
+  limit?: number;
+}
+
+/**
+ * 历史记录查询结果（一页）
+ */
+export interface HistoryPage {
+  items: HistoryItem[];
+  /** 下一页的游标，没有更多结果时为 null */
+  nextCursor: string | null;
+  /** 符合条件的总条数 */
+  total: number;
+  /** 尚未告知用户的历史记录恢复结果，只在恢复后的第一次查询中返回 */
+  recoveries: HistoryRecovery[];
+}
+
+/**
+ * 历史记录损坏后的恢复结果
+ */
+export interface HistoryRecovery {
+  /** 损坏文件的备份路径 */
+  backupPath: string;
+  /** 恢复的记录数 */
+  recovered: number;
+  /** 丢失的记录数（估计值），无法估计时为 null */
+  lost: number | null;
+}
+
+/**
+ * 重复下载检查结果
+ */
+export interface DuplicateCheck {
+  /** 最近一次下载同一视频、同一格式的历史记录 */
+  item: HistoryItem | null;
+  /** 之前下载的文件是否仍然存在 */
+  fileExists: boolean;
 }
 
+/**
+ * 删除单条历史记录的结果
+ */
+export interface DeleteResult {
+  /** 记录 ID */
+  id: string;
+  /** 记录是否已删除 */
+  deleted: boolean;
+  /** 已删除的文件（媒体文件及其附属文件） */
+  removedFiles: string[];
+  /** 失败时的错误消息 */
+  error: string | null;
+}
+
+/**
+ * 重新关联移动过的文件的结果
+ */
+export interface RelinkReport {
+  /** 找到新位置的记录数 */
+  relinked: number;
+  /** 仍然找不到文件的记录数 */
+  missing: number;
+}
+
+/**
+ * 历史记录导出格式（HTML 报告只用于查看，不能导入）
+ */
+export type HistoryFormat = 'json' | 'csv' | 'html';
+
+/**
+ * 导入历史记录时与已有记录的合并方式
+ * - skip: 保留已有记录，只添加新记录
+ * - overwrite: 用导入的记录覆盖已有的同一条记录
+ * - replace: 清空现有历史记录后导入
+ */
+export type MergeStrategy = 'skip' | 'overwrite' | 'replace';
+
+/**
+ * 导入历史记录的结果
+ */
+export interface HistoryImportResult {
+  /** 新增的记录数 */
+  added: number;
+  /** 覆盖的记录数 */
+  updated: number;
+  /** 因已存在而跳过的记录数 */
+  skipped: number;
+}
+
+/**
+ * 重复下载的处理方式（选择跳过时不发起下载）
+ * - new: 没有重复，正常下载（默认）
+ * - redownload / keepBoth: 用户明确选择再次下载，不使用下载存档
+ */
+export type DuplicateAction = 'new' | 'redownload' | 'keepBoth';
+
 /**
  * 更新信息
  */
@@ -156,12 +304,77 @@ export interface UpdateProgress {
   total: string;
 }
 
+/**
+ * 导入链接的校验结果
+ * - ok: 可以下载
+ * - duplicate: 与本次导入中前面的链接重复
+ * - inHistory: 已在下载历史中
+ * - inQueue: 正在下载或已加入计划
+ * - playlist: 播放列表或频道链接
+ * - invalid: 无效的链接
+ */
+export type ImportStatus = 'ok' | 'duplicate' | 'inHistory' | 'inQueue' | 'playlist' | 'invalid';
+
+/**
+ * 导入结果中的一项
+ */
+export interface ImportEntry {
+  /** 行号（从 1 开始） */
+  line: number;
+  /** 原始输入 */
+  input: string;
+  /** 规范化后的链接 */
+  url: string | null;
+  /** 校验结果 */
+  status: ImportStatus;
+  /** 无效时的错误消息 */
+  message: string | null;
+}
+
+/**
+ * 批量导入结果
+ */
+export interface ImportReport {
+  /** 逐行的校验结果 */
+  entries: ImportEntry[];
+  /** 可以下载的链接 */
+  urls: string[];
+}
+
+/**
+ * yt-dlp 输出行
+ */
+export interface OutputLine {
+  /** 输出来源：执行的命令行、标准输出或错误输出 */
+  stream: 'command' | 'stdout' | 'stderr';
+  /** 时间戳（毫秒） */
+  timestamp: number;
+  /** 内容 */
+  text: string;
+}
+
+/**
+ * 任务的 yt-dlp 输出记录
+ */
+export interface TaskLog {
+  /** 任务 ID */
+  taskId: string;
+  /** 输出行（从旧到新） */
+  lines: OutputLine[];
+  /** 超出容量被丢弃的最早行数 */
+  dropped: number;
+}
+
 /**
  * 错误响应
  */
 export interface ErrorResponse {
-  /** 错误类型 */
+  /** 错误类型（稳定的错误代码，如 "InsufficientSpace"） */
   errorType: string;
   /** 错误消息 */
   message: string;
+  /** 技术细节 */
+  details: string;
+  /** 是否可以重试 */
+  retryable: boolean;
 }